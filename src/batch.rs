@@ -0,0 +1,382 @@
+use crate::segment::{Segment, SegmentType};
+use crate::splitter::Splitter;
+use crate::{RipError, RipString};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+use core::ops::{Range, RangeBounds};
+
+/// Moves segments out of a rope in index order, splitting them at requested bounds.
+struct NodeCursor {
+    nodes: vec::IntoIter<Segment>,
+    /// Start index and content of the not yet consumed part of the current segment.
+    current: Option<(usize, SegmentType)>,
+}
+
+impl NodeCursor {
+    /// Moves the content of `range` into `out`, dropping everything before it.
+    fn take(&mut self, range: Range<usize>, out: &mut Vec<Segment>) {
+        loop {
+            let (start, mut tp) = match self.current.take() {
+                Some(current) => current,
+                None => match self.nodes.next() {
+                    Some(node) => (node.index(), node.into_tp()),
+                    None => return,
+                },
+            };
+            let end = start + tp.len();
+
+            if end <= range.start {
+                continue;
+            }
+            if start < range.start {
+                let rest = tp.split(range.start - start);
+                self.current = Some((range.start, rest));
+                continue;
+            }
+            if start >= range.end {
+                self.current = Some((start, tp));
+                return;
+            }
+            if end <= range.end {
+                push_segment(out, tp);
+            } else {
+                let rest = tp.split(range.end - start);
+                push_segment(out, tp);
+                self.current = Some((range.end, rest));
+                return;
+            }
+        }
+    }
+}
+
+/// Appends a segment, merging it into the previous one when either of them is small.
+pub(crate) fn push_segment(out: &mut Vec<Segment>, tp: SegmentType) {
+    if tp.is_empty() {
+        return;
+    }
+    let tp = match out.last_mut() {
+        Some(last) => last.modify(|last| last.merge_small(tp)),
+        None => Some(tp),
+    };
+    if let Some(tp) = tp {
+        out.push(Segment::new(0, tp));
+    }
+}
+
+impl RipString {
+    /// Applies sorted, non-overlapping edits in a single pass over the segments and
+    /// fixes the indices once. Ranges are given in the coordinates of the original text.
+    pub(crate) fn apply_edits<S: AsRef<str>>(&mut self, edits: &[(Range<usize>, S)]) {
+        let len = self.len();
+        let mut cursor = NodeCursor {
+            nodes: mem::take(&mut self.nodes).into_iter(),
+            current: None,
+        };
+        let mut nodes = Vec::new();
+        let mut kept = 0;
+        for (range, text) in edits {
+            cursor.take(kept..range.start, &mut nodes);
+            let text = self.inserted_text(text.as_ref());
+            for tp in Splitter::new(&text, self.segmenter) {
+                push_segment(&mut nodes, tp);
+            }
+            kept = range.end;
+        }
+        cursor.take(kept..len, &mut nodes);
+        self.reset_nodes(nodes);
+    }
+
+    /// Applies edits given in the coordinates of the original text in one pass. The
+    /// edits may come in any order but must not overlap; on error the text is left
+    /// unchanged. Insertions at the same index keep their order and go before a
+    /// replacement starting there.
+    pub fn edit_all<S: AsRef<str>>(&mut self, edits: &[(Range<usize>, S)]) -> Result<(), RipError> {
+        let mut order = (0..edits.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| (edits[*i].0.start, edits[*i].0.end));
+        let mut end = 0;
+        for i in &order {
+            let range = &edits[*i].0;
+            self.check_range(range)?;
+            if range.start < end {
+                return Err(RipError::OverlappingEdits(range.start));
+            }
+            end = range.end;
+        }
+
+        let sorted = order
+            .into_iter()
+            .map(|i| (edits[i].0.clone(), edits[i].1.as_ref()))
+            .collect::<Vec<_>>();
+        self.apply_edits(&sorted);
+        Ok(())
+    }
+
+    /// Removes every unit for which `f` returns false. Like `Drain`, `f` sees `\r\n` as a
+    /// single item, so the pair is kept or removed as a whole.
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut f: F) {
+        let mut keep = Vec::with_capacity(self.len());
+        let mut prev_cr = false;
+        self.for_each_unit(0..self.len(), |unit| {
+            if prev_cr {
+                prev_cr = false;
+                if unit == "\n" {
+                    let kept = f("\r\n");
+                    keep.extend([kept, kept]);
+                    return;
+                }
+                keep.push(f("\r"));
+            }
+            if unit == "\r" {
+                prev_cr = true;
+            } else {
+                keep.push(f(unit));
+            }
+        });
+        if prev_cr {
+            keep.push(f("\r"));
+        }
+
+        let mut keep = keep.into_iter();
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        for node in mem::take(&mut self.nodes) {
+            let mut tp = node.into_tp();
+            tp.retain(|_| keep.next().unwrap_or(true));
+            push_segment(&mut nodes, tp);
+        }
+        self.reset_nodes(nodes);
+    }
+
+    /// Moves the text of `src` to `dest`, given in the coordinates before the move. The
+    /// moved segments are spliced as they are, only the segments at the bounds are split.
+    /// Panics if a bound is out of range or `dest` lies inside `src`.
+    pub fn move_range(&mut self, src: Range<usize>, dest: usize) {
+        self.check_or_panic(&src);
+        self.check_or_panic(&(dest..dest));
+        assert!(
+            dest <= src.start || dest >= src.end,
+            "Destination {} is inside the moved range {:?}",
+            dest,
+            src
+        );
+        if dest <= src.start {
+            self.rearrange(&[dest, src.start, src.end], &[0, 2, 1, 3]);
+        } else {
+            self.rearrange(&[src.start, src.end, dest], &[0, 2, 1, 3]);
+        }
+    }
+
+    /// Exchanges the text of two non-overlapping ranges. Panics if they overlap or are out
+    /// of bounds.
+    pub fn swap_range(&mut self, a: Range<usize>, b: Range<usize>) {
+        self.check_or_panic(&a);
+        self.check_or_panic(&b);
+        let (first, second) = if a.start <= b.start { (a, b) } else { (b, a) };
+        if first.end > second.start {
+            panic!("{}", RipError::OverlappingEdits(second.start));
+        }
+        self.rearrange(
+            &[first.start, first.end, second.start, second.end],
+            &[0, 3, 2, 1, 4],
+        );
+    }
+
+    /// Cuts the text at the ascending `bounds` and reassembles the pieces in `order`.
+    fn rearrange(&mut self, bounds: &[usize], order: &[usize]) {
+        let len = self.len();
+        let mut cursor = NodeCursor {
+            nodes: mem::take(&mut self.nodes).into_iter(),
+            current: None,
+        };
+        let mut start = 0;
+        let mut pieces = Vec::with_capacity(bounds.len() + 1);
+        for end in bounds.iter().copied().chain(Some(len)) {
+            let mut piece = Vec::new();
+            cursor.take(start..end, &mut piece);
+            pieces.push(piece);
+            start = end;
+        }
+
+        let mut nodes = Vec::with_capacity(pieces.iter().map(Vec::len).sum());
+        for i in order {
+            for node in mem::take(&mut pieces[*i]) {
+                push_segment(&mut nodes, node.into_tp());
+            }
+        }
+        self.reset_nodes(nodes);
+    }
+
+    /// Feeds the text of the range through `f` one chunk per segment the range touches
+    /// and replaces the range with the concatenated results. Chunks end on unit
+    /// boundaries, so a `\r\n` pair may be split between two of them. Panics if the
+    /// range is inverted or out of bounds.
+    pub fn transform_range<R, F>(&mut self, range: R, mut f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&str) -> String,
+    {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        let mut text = String::new();
+        let mut chunk = String::new();
+        self.for_each_node_range(range.clone(), |node, range| {
+            chunk.clear();
+            node.for_each_unit(range, |unit| chunk.push_str(unit));
+            text.push_str(&f(&chunk));
+        });
+        self.apply_edits(&[(range, text)]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipError, RipString};
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_apply_edits() {
+        let mut a = RipString::from("Hello world. Привет мир 🏡!");
+        a.apply_edits(&[
+            (0..0, ">> "),
+            (5..5, ","),
+            (6..11, "rope"),
+            (13..19, "Пока"),
+            (24..25, "🌋"),
+            (26..26, "!"),
+        ]);
+        assert_eq!(a.to_string(), ">> Hello, rope. Пока мир 🌋!!");
+        assert_eq!(a.len(), 28);
+
+        let text = "0123456789".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.apply_edits(&[(1000..2000, ""), (2500..2600, "x")]);
+        let mut expected = text.clone();
+        expected.replace_range(2500..2600, "x");
+        expected.replace_range(1000..2000, "");
+        assert_eq!(a.to_string(), expected);
+
+        let mut a = RipString::from("abc");
+        a.apply_edits(&[(0..3, "")]);
+        assert_eq!(a.to_string(), "");
+        assert_eq!(a.len(), 0);
+    }
+
+    #[test]
+    fn test_edit_all() {
+        let mut a = RipString::from("Hello мир 🏡!");
+        assert_eq!(
+            a.edit_all(&[(11..12, "."), (0..5, "Bye"), (5..5, ","), (5..5, " big")]),
+            Ok(())
+        );
+        assert_eq!(a.to_string(), "Bye, big мир 🏡.");
+
+        let mut a = RipString::from("Hello");
+        assert_eq!(
+            a.edit_all(&[(0..3, "a"), (2..4, "b")]),
+            Err(RipError::OverlappingEdits(2))
+        );
+        assert_eq!(
+            a.edit_all(&[(0..1, "a"), (4..6, "b")]),
+            Err(RipError::OutOfBounds { index: 6, len: 5 })
+        );
+        assert_eq!(a.to_string(), "Hello");
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut a = RipString::from("a\u{7}б\tc\u{200b}🏡 e\u{301}\r\n");
+        a.retain(|unit| !unit.chars().any(|ch| ch.is_control() || ch == '\u{200b}'));
+        assert_eq!(a.to_string(), "aбc🏡 e\u{301}");
+
+        let text = "ab мир\n".repeat(500);
+        let mut a = RipString::from(text.as_str());
+        a.retain(|unit| unit != "\n" && unit != " ");
+        assert_eq!(a.to_string(), "abмир".repeat(500));
+        assert_eq!(a.validate(), Ok(()));
+
+        let mut a = RipString::from("a\r\nb\rc\n\r");
+        let mut seen = Vec::new();
+        a.retain(|unit| {
+            seen.push(String::from(unit));
+            unit != "\r\n"
+        });
+        assert_eq!(seen, ["a", "\r\n", "b", "\r", "c", "\n", "\r"]);
+        assert_eq!(a.to_string(), "ab\rc\n\r");
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_move_swap_range() {
+        let mut a = RipString::from("Hello мир 🏡!");
+        a.move_range(10..11, 0);
+        assert_eq!(a.to_string(), "🏡Hello мир !");
+        a.move_range(0..1, 12);
+        assert_eq!(a.to_string(), "Hello мир !🏡");
+        a.move_range(5..9, 5);
+        assert_eq!(a.to_string(), "Hello мир !🏡");
+        a.swap_range(11..12, 0..5);
+        assert_eq!(a.to_string(), "🏡 мир !Hello");
+
+        let text = "0123456789".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.move_range(100..2100, 2900);
+        assert_eq!(
+            a.to_string(),
+            [
+                &text[..100],
+                &text[2100..2900],
+                &text[100..2100],
+                &text[2900..]
+            ]
+            .concat()
+        );
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Edits overlap at index 3")]
+    fn test_swap_overlapping() {
+        RipString::from("Hello").swap_range(3..5, 1..4);
+    }
+
+    #[test]
+    fn test_transform_range() {
+        let rot13 = |chunk: &str| {
+            chunk
+                .chars()
+                .map(|ch| match ch {
+                    'a'..='m' | 'A'..='M' => (ch as u8 + 13) as char,
+                    'n'..='z' | 'N'..='Z' => (ch as u8 - 13) as char,
+                    _ => ch,
+                })
+                .collect()
+        };
+        let mut a = RipString::from("Hello, мир! Secret");
+        a.transform_range(12..18, rot13);
+        assert_eq!(a.to_string(), "Hello, мир! Frperg");
+
+        let text = "abc ".repeat(1000);
+        let mut a = RipString::from(text.as_str());
+        let mut chunks = 0;
+        a.transform_range(2..3998, |chunk| {
+            chunks += 1;
+            "*".repeat(chunk.len())
+        });
+        assert!(chunks > 1);
+        assert_eq!(a.to_string(), format!("ab{}c ", "*".repeat(3996)));
+
+        a.transform_range(..2, |chunk| chunk.to_uppercase());
+        assert_eq!(a.len(), 4000);
+        assert!(a.to_string().starts_with("AB*"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Range starts at 3 after its end 1")]
+    fn test_transform_inverted_range() {
+        let (start, end) = (3, 1);
+        RipString::from("Hello").transform_range(start..end, |chunk| chunk.into());
+    }
+}
@@ -0,0 +1,145 @@
+//! Deterministic edit workloads for benchmarks.
+
+use crate::rng::SplitMix;
+use crate::RipString;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::min;
+use core::ops::Range;
+
+/// Words the generated text is made of. None of them joins with its neighbours into a
+/// grapheme cluster, so every char is one unit.
+const WORDS: &[&str] = &[
+    "the", "rope", "of", "a", "text", "edit", "мир", "café", "naïve", "数据", "fn", "let", "x",
+    "0", "42", "_", "{", "}", ";",
+];
+
+/// Kind of editing session to simulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workload {
+    /// Single character inserts at a moving cursor, with occasional backspaces and
+    /// jumps.
+    Typing,
+    /// Large blocks of text inserted at random positions.
+    PasteStorm,
+    /// Short deletions spread over the whole text.
+    ScatteredDeletions,
+}
+
+impl Workload {
+    /// Generates `count` edits for a text of `len` units. The same seed always produces
+    /// the same edits, each of which is valid after the ones before it are applied.
+    pub fn generate(self, seed: u64, len: usize, count: usize) -> Vec<(Range<usize>, String)> {
+        let mut rng = SplitMix(seed);
+        let mut len = len;
+        let mut cursor = rng.below(len + 1);
+        let mut edits = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let (range, text) = match self {
+                Workload::Typing => {
+                    if rng.below(40) == 0 {
+                        cursor = rng.below(len + 1);
+                    }
+                    if cursor > 0 && rng.below(8) == 0 {
+                        cursor -= 1;
+                        (cursor..cursor + 1, String::new())
+                    } else {
+                        let text = match rng.below(6) {
+                            0 => String::from(" "),
+                            1 if rng.below(4) == 0 => String::from("\n"),
+                            _ => {
+                                let word = WORDS[rng.below(WORDS.len())];
+                                word.chars().take(1).collect()
+                            }
+                        };
+                        cursor += 1;
+                        (cursor - 1..cursor - 1, text)
+                    }
+                }
+                Workload::PasteStorm => {
+                    let index = rng.below(len + 1);
+                    let size = 64 + rng.below(4032);
+                    (index..index, rng.text(size))
+                }
+                Workload::ScatteredDeletions => {
+                    let start = rng.below(len + 1);
+                    (start..min(start + 1 + rng.below(16), len), String::new())
+                }
+            };
+            len = len - range.len() + text.chars().count();
+            edits.push((range, text));
+        }
+        edits
+    }
+}
+
+/// Generates text of roughly `len` units for the initial state of a workload.
+pub fn seeded_text(seed: u64, len: usize) -> String {
+    SplitMix(seed).text(len)
+}
+
+/// Applies the edits in order. Indices are clamped to the current length, so a script
+/// can be replayed against a text of a different size.
+pub fn run(rope: &mut RipString, edits: &[(Range<usize>, String)]) {
+    for (range, text) in edits {
+        let len = rope.len();
+        let start = min(range.start, len);
+        rope.edit(start..min(range.end, len).max(start), text);
+    }
+}
+
+impl SplitMix {
+    /// Text of `len` chars made of words, spaces and line breaks.
+    fn text(&mut self, len: usize) -> String {
+        let mut text = String::new();
+        let mut count = 0;
+        while count < len {
+            let piece = match self.below(10) {
+                0 => "\n",
+                1..=3 => " ",
+                _ => WORDS[self.below(WORDS.len())],
+            };
+            for ch in piece.chars().take(len - count) {
+                text.push(ch);
+                count += 1;
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench_util::{run, seeded_text, Workload};
+    use crate::RipString;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_workloads() {
+        let text = seeded_text(7, 500);
+        assert_eq!(text.chars().count(), 500);
+        assert_eq!(text, seeded_text(7, 500));
+        assert_ne!(text, seeded_text(8, 500));
+
+        for workload in [
+            Workload::Typing,
+            Workload::PasteStorm,
+            Workload::ScatteredDeletions,
+        ] {
+            let edits = workload.generate(1, 500, 200);
+            assert_eq!(edits, workload.generate(1, 500, 200));
+
+            let mut chars = text.chars().collect::<Vec<_>>();
+            for (range, new) in &edits {
+                assert!(range.start <= range.end && range.end <= chars.len());
+                chars.splice(range.clone(), new.chars());
+            }
+
+            let mut rope = RipString::from(text.as_str());
+            run(&mut rope, &edits);
+            assert_eq!(rope.to_string(), chars.iter().collect::<String>());
+        }
+    }
+}
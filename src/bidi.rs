@@ -0,0 +1,116 @@
+use crate::RipString;
+use alloc::vec::Vec;
+use core::ops::Range;
+use unicode_bidi::BidiInfo;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidiLine {
+    /// Paragraph embedding level.
+    pub level: u8,
+    /// Directional runs in visual (left to right) order.
+    pub runs: Vec<BidiRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidiRun {
+    pub range: Range<usize>,
+    pub level: u8,
+}
+
+impl BidiLine {
+    pub fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+}
+
+impl BidiRun {
+    pub fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+}
+
+impl RipString {
+    /// Runs the Unicode bidirectional algorithm (UAX #9) over the given line.
+    pub fn bidi_line(&self, line: usize) -> Option<BidiLine> {
+        let range = self.line_range(line)?;
+        let (text, offsets) = self.text_with_offsets(range.clone());
+        let info = BidiInfo::new(&text, None);
+        let para = match info.paragraphs.first() {
+            Some(para) => para,
+            None => {
+                return Some(BidiLine {
+                    level: 0,
+                    runs: Vec::new(),
+                })
+            }
+        };
+
+        let (levels, runs) = info.visual_runs(para, para.range.clone());
+        let to_unit = |byte: usize| range.start + offsets.partition_point(|offset| *offset < byte);
+        let runs = runs
+            .into_iter()
+            .map(|run| BidiRun {
+                level: levels[run.start].number(),
+                range: to_unit(run.start)..to_unit(run.end),
+            })
+            .collect();
+
+        Some(BidiLine {
+            level: para.level.number(),
+            runs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BidiLine, BidiRun, RipString};
+    use alloc::vec;
+
+    #[test]
+    fn test_bidi_line() {
+        let a = RipString::from("abc אבג def\nשלום world\n");
+        let line = a.bidi_line(0).unwrap();
+        assert!(!line.is_rtl());
+        assert_eq!(
+            line,
+            BidiLine {
+                level: 0,
+                runs: vec![
+                    BidiRun {
+                        range: 0..4,
+                        level: 0
+                    },
+                    BidiRun {
+                        range: 4..7,
+                        level: 1
+                    },
+                    BidiRun {
+                        range: 7..11,
+                        level: 0
+                    },
+                ],
+            }
+        );
+
+        let line = a.bidi_line(1).unwrap();
+        assert!(line.is_rtl());
+        assert_eq!(
+            line.runs,
+            vec![
+                BidiRun {
+                    range: 17..22,
+                    level: 2
+                },
+                BidiRun {
+                    range: 12..17,
+                    level: 1
+                },
+            ]
+        );
+        assert!(line.runs[1].is_rtl());
+
+        assert_eq!(a.bidi_line(2).unwrap().runs, vec![]);
+        assert_eq!(a.bidi_line(3), None);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::RipString;
+use alloc::string::String;
+use core::ops::Range;
+
+/// Common interface of text storages, so that code can be written once for `RipString`
+/// and `String`.
+///
+/// Indices are in the units of the buffer: the units of `RipString` and bytes of
+/// `String`. Both agree on ASCII text.
+pub trait TextBuffer {
+    /// Returns the length in units.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replaces the range with `text`.
+    fn edit(&mut self, range: Range<usize>, text: &str);
+
+    /// Returns the text of the range.
+    fn slice(&self, range: Range<usize>) -> String;
+
+    /// Calls `f` with consecutive pieces of the text of the range.
+    fn for_each_chunk(&self, range: Range<usize>, f: &mut dyn FnMut(&str));
+}
+
+impl TextBuffer for RipString {
+    fn len(&self) -> usize {
+        RipString::len(self)
+    }
+
+    fn edit(&mut self, range: Range<usize>, text: &str) {
+        RipString::edit(self, range, text)
+    }
+
+    fn slice(&self, range: Range<usize>) -> String {
+        self.collect_range(range)
+    }
+
+    fn for_each_chunk(&self, range: Range<usize>, f: &mut dyn FnMut(&str)) {
+        RipString::for_each_chunk(self, range, f)
+    }
+}
+
+impl TextBuffer for String {
+    fn len(&self) -> usize {
+        String::len(self)
+    }
+
+    fn edit(&mut self, range: Range<usize>, text: &str) {
+        self.replace_range(range, text)
+    }
+
+    fn slice(&self, range: Range<usize>) -> String {
+        String::from(&self[range])
+    }
+
+    fn for_each_chunk(&self, range: Range<usize>, f: &mut dyn FnMut(&str)) {
+        f(&self[range])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipString, TextBuffer};
+    use alloc::string::String;
+
+    fn exercise<B: TextBuffer>(buffer: &mut B) -> String {
+        buffer.edit(5..5, ",");
+        buffer.edit(7..12, "there");
+        let mut text = buffer.slice(0..3);
+        buffer.for_each_chunk(3..buffer.len(), &mut |chunk| text.push_str(chunk));
+        text
+    }
+
+    #[test]
+    fn test_text_buffer() {
+        let mut a = RipString::from("Hello world!");
+        let mut b = String::from("Hello world!");
+        assert_eq!(exercise(&mut a), "Hello, there!");
+        assert_eq!(exercise(&mut b), "Hello, there!");
+        assert!(!TextBuffer::is_empty(&a) && TextBuffer::is_empty(&String::new()));
+
+        let c = RipString::from("abc мир 🏡");
+        let mut chunks = String::new();
+        TextBuffer::for_each_chunk(&c, 2..9, &mut |chunk| {
+            chunks.push_str(chunk);
+            chunks.push('|');
+        });
+        assert_eq!(chunks, "c |мир |🏡|");
+    }
+}
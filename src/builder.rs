@@ -0,0 +1,74 @@
+use crate::segmenter::{Segmenter, DEFAULT};
+use crate::splitter::{Partition, StreamSplitter};
+use crate::RipString;
+use alloc::vec::Vec;
+
+/// Builds a `RipString` from many small pieces. Complete blocks are segmented as soon as
+/// they arrive and only the unfinished tail is kept as text.
+pub struct RopeBuilder {
+    splitter: StreamSplitter,
+    partitions: Vec<Partition>,
+    segmenter: &'static dyn Segmenter,
+}
+
+impl RopeBuilder {
+    pub fn new() -> RopeBuilder {
+        RopeBuilder::with_segmenter(DEFAULT)
+    }
+
+    pub fn with_segmenter(segmenter: &'static dyn Segmenter) -> RopeBuilder {
+        RopeBuilder {
+            splitter: StreamSplitter::new(segmenter),
+            partitions: Vec::new(),
+            segmenter,
+        }
+    }
+
+    pub fn append(&mut self, chunk: &str) {
+        let done = self
+            .splitter
+            .push(chunk.as_bytes())
+            .expect("str chunks are valid UTF-8");
+        self.partitions.extend(done);
+    }
+
+    pub fn finish(mut self) -> RipString {
+        let rest = self.splitter.finish().expect("str chunks are valid UTF-8");
+        self.partitions.extend(rest);
+        RipString::from_partitions(self.partitions, self.segmenter)
+    }
+}
+
+impl Default for RopeBuilder {
+    fn default() -> Self {
+        RopeBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipString, RopeBuilder};
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn test_rope_builder() {
+        let mut builder = RopeBuilder::new();
+        let mut text = String::new();
+        for i in 0..500 {
+            let piece = if i % 7 == 0 {
+                "мир 🏡\r\n"
+            } else {
+                "word "
+            };
+            builder.append(piece);
+            text.push_str(piece);
+        }
+        let a = builder.finish();
+        let b = RipString::from(text.as_str());
+        assert_eq!(a.to_string(), text);
+        assert_eq!(a.len(), b.len());
+        assert_eq!(a.nodes, b.nodes);
+
+        assert_eq!(RopeBuilder::new().finish().len(), 0);
+    }
+}
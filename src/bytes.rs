@@ -0,0 +1,54 @@
+//! Export of the content as [`Bytes`](https://docs.rs/bytes) chunks for network I/O.
+
+use crate::segment::SegmentType;
+use crate::RipString;
+use ::bytes::Bytes;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+impl RipString {
+    /// Returns the UTF-8 text of the range as a sequence of chunks.
+    pub fn to_bytes_chunks(&self, range: Range<usize>) -> Vec<Bytes> {
+        let mut chunks = Vec::new();
+        self.for_each_chunk(range, |chunk| {
+            chunks.push(Bytes::copy_from_slice(chunk.as_bytes()))
+        });
+        chunks
+    }
+
+    /// Converts the string into a sequence of chunks. The buffers of ASCII segments are
+    /// handed over without copying.
+    pub fn into_bytes_chunks(self) -> Vec<Bytes> {
+        let mut chunks = Vec::new();
+        for node in self.nodes {
+            match node.into_tp() {
+                SegmentType::Ascii(val) if !val.is_empty() => chunks.push(Bytes::from(val)),
+                SegmentType::Ascii(_) => {}
+                tp => {
+                    let len = tp.len();
+                    tp.for_each_chunk(0..len, |chunk| {
+                        chunks.push(Bytes::copy_from_slice(chunk.as_bytes()))
+                    });
+                }
+            }
+        }
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+
+    #[test]
+    fn test_bytes_chunks() {
+        let a = RipString::from("Hello мир 🏡!");
+        let chunks = a.to_bytes_chunks(3..9);
+        assert_eq!(chunks.concat(), "lo мир".as_bytes());
+
+        let chunks = a.into_bytes_chunks();
+        assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+        assert_eq!(chunks.concat(), "Hello мир 🏡!".as_bytes());
+        assert!(RipString::new().into_bytes_chunks().is_empty());
+    }
+}
@@ -0,0 +1,440 @@
+use crate::batch::push_segment;
+use crate::segment::{Segment, SegmentType};
+use crate::segmenter::{Graphemes, Segmenter};
+use crate::splitter::Splitter;
+use crate::stats::{is_closing, is_terminator};
+use crate::RipString;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "case-folding")]
+use caseless::Caseless;
+use core::cmp::{max, min};
+#[cfg(feature = "case-folding")]
+use core::iter;
+use core::ops::{Range, RangeBounds};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Case {
+    Upper,
+    Lower,
+    #[cfg(feature = "case-folding")]
+    Fold,
+}
+
+impl Case {
+    pub(crate) fn push(self, ch: char, out: &mut String) {
+        match self {
+            Case::Upper => out.extend(ch.to_uppercase()),
+            Case::Lower => out.extend(ch.to_lowercase()),
+            #[cfg(feature = "case-folding")]
+            Case::Fold => out.extend(iter::once(ch).default_case_fold()),
+        }
+    }
+
+    fn map_ascii(self, val: &mut [u8]) {
+        match self {
+            Case::Upper => val.make_ascii_uppercase(),
+            Case::Lower => val.make_ascii_lowercase(),
+            #[cfg(feature = "case-folding")]
+            Case::Fold => val.make_ascii_lowercase(),
+        }
+    }
+
+    /// Maps a char of a Utf8 segment if the result still belongs to a Utf8 segment.
+    fn map_char(self, ch: char) -> Option<char> {
+        let mapped = match self {
+            Case::Upper => single(ch.to_uppercase())?,
+            Case::Lower => single(ch.to_lowercase())?,
+            #[cfg(feature = "case-folding")]
+            Case::Fold => single(iter::once(ch).default_case_fold())?,
+        };
+        if mapped.len_utf8() <= 2 && !mapped.is_ascii_alphabetic() {
+            Some(mapped)
+        } else {
+            None
+        }
+    }
+
+    /// Maps a grapheme of a Unicode segment if the result is still a single long grapheme.
+    fn map_grapheme(self, grapheme: &str, segmenter: &dyn Segmenter) -> Option<String> {
+        let mut mapped = String::with_capacity(grapheme.len());
+        for ch in grapheme.chars() {
+            self.push(ch, &mut mapped);
+        }
+        if mapped.len() > 2 && Graphemes::new(&mapped, segmenter).nth(1).is_none() {
+            Some(mapped)
+        } else {
+            None
+        }
+    }
+
+    /// Converts the segment keeping its layout. If the mapping changes the classification
+    /// of some unit, the converted text is returned instead so it can be split again.
+    pub(crate) fn convert(
+        self,
+        tp: &SegmentType,
+        segmenter: &dyn Segmenter,
+    ) -> Result<SegmentType, String> {
+        let converted = match tp {
+            SegmentType::Ascii(val) => {
+                let mut val = val.clone();
+                self.map_ascii(&mut val);
+                Some(SegmentType::Ascii(val))
+            }
+            SegmentType::Utf8(val) => val
+                .iter()
+                .map(|ch| self.map_char(*ch))
+                .collect::<Option<Vec<_>>>()
+                .map(SegmentType::Utf8),
+            SegmentType::Unicode(val) => val
+                .iter()
+                .map(|grapheme| self.map_grapheme(grapheme, segmenter))
+                .collect::<Option<Vec<_>>>()
+                .map(SegmentType::Unicode),
+        };
+
+        converted.ok_or_else(|| {
+            let mut text = String::new();
+            for ch in tp.chars(0..tp.len()) {
+                self.push(ch, &mut text);
+            }
+            text
+        })
+    }
+}
+
+/// Pushes the titlecase mapping of `ch`, which differs from the uppercase one for
+/// digraphs, ligatures and Greek letters with ypogegrammeni.
+fn push_titlecase(ch: char, out: &mut String) {
+    let mapped = match ch {
+        '\u{1c4}'..='\u{1c6}' => "\u{1c5}",
+        '\u{1c7}'..='\u{1c9}' => "\u{1c8}",
+        '\u{1ca}'..='\u{1cc}' => "\u{1cb}",
+        '\u{1f1}'..='\u{1f3}' => "\u{1f2}",
+        'ß' => "Ss",
+        'ﬀ' => "Ff",
+        'ﬁ' => "Fi",
+        'ﬂ' => "Fl",
+        'ﬃ' => "Ffi",
+        'ﬄ' => "Ffl",
+        'ﬅ' | 'ﬆ' => "St",
+        'և' => "Եւ",
+        '\u{1f80}'..='\u{1faf}' => {
+            out.push(char::from_u32(ch as u32 | 0x8).unwrap_or(ch));
+            return;
+        }
+        '\u{1fb3}' => "\u{1fbc}",
+        '\u{1fc3}' => "\u{1fcc}",
+        '\u{1ff3}' => "\u{1ffc}",
+        _ => {
+            out.extend(ch.to_uppercase());
+            return;
+        }
+    };
+    out.push_str(mapped);
+}
+
+/// Titlecases the first char of the unit and lowercases the rest of it.
+fn push_titlecase_unit(unit: &str, out: &mut String) {
+    let mut chars = unit.chars();
+    if let Some(ch) = chars.next() {
+        push_titlecase(ch, out);
+    }
+    for ch in chars {
+        Case::Lower.push(ch, out);
+    }
+}
+
+fn single<I: Iterator<Item = char>>(mut iter: I) -> Option<char> {
+    let ch = iter.next()?;
+    if iter.next().is_none() {
+        Some(ch)
+    } else {
+        None
+    }
+}
+
+impl RipString {
+    pub fn to_uppercase(&self) -> RipString {
+        self.case_converted(Case::Upper)
+    }
+
+    pub fn to_lowercase(&self) -> RipString {
+        self.case_converted(Case::Lower)
+    }
+
+    pub fn make_uppercase(&mut self) {
+        self.convert_case(Case::Upper, 0..self.len())
+    }
+
+    pub fn make_lowercase(&mut self) {
+        self.convert_case(Case::Lower, 0..self.len())
+    }
+
+    /// Uppercases the units of the range in place.
+    pub fn to_uppercase_in_place<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        self.convert_case(Case::Upper, range)
+    }
+
+    /// Lowercases the units of the range in place.
+    pub fn to_lowercase_in_place<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        self.convert_case(Case::Lower, range)
+    }
+
+    /// Titlecases every word intersecting the range: its first unit is titlecased and
+    /// the rest are lowercased. Only the part of a word inside the range is changed.
+    /// Panics if the range is inverted or out of bounds.
+    pub fn to_titlecase<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        let mut edits = Vec::new();
+        for word in self.words_in(range.clone()) {
+            let start = max(word.start, range.start);
+            let end = min(word.end, range.end);
+            let mut index = start;
+            let mut text = String::new();
+            self.for_each_unit(start..end, |unit| {
+                if index == word.start {
+                    push_titlecase_unit(unit, &mut text);
+                } else {
+                    for ch in unit.chars() {
+                        Case::Lower.push(ch, &mut text);
+                    }
+                }
+                index += 1;
+            });
+            if self.collect_range(start..end) != text {
+                edits.push((start..end, text));
+            }
+        }
+        self.apply_edits(&edits);
+    }
+
+    /// Titlecases the first letter of every sentence starting inside the range. Panics if
+    /// the range is inverted or out of bounds.
+    pub fn capitalize_sentences<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        let before = self.skip_whitespace_backward(range.start);
+        let before = self.scan_back_while(before, is_closing);
+        let mut at_start = before == 0
+            || (before < range.start && self.scan_back_while(before, is_terminator) < before);
+        let mut terminated = false;
+
+        let mut edits = Vec::new();
+        let mut index = range.start;
+        self.for_each_unit(range, |unit| {
+            let first = unit.chars().next().unwrap_or_default();
+            if first.is_whitespace() {
+                at_start |= terminated;
+                terminated = false;
+            } else if is_terminator(unit) {
+                terminated = true;
+            } else if first.is_alphanumeric() {
+                if at_start && first.is_alphabetic() {
+                    let mut text = String::new();
+                    push_titlecase(first, &mut text);
+                    text.push_str(&unit[first.len_utf8()..]);
+                    if text != unit {
+                        edits.push((index..index + 1, text));
+                    }
+                }
+                at_start = false;
+                terminated = false;
+            } else if !is_closing(unit) {
+                terminated = false;
+            }
+            index += 1;
+        });
+        self.apply_edits(&edits);
+    }
+
+    /// Returns the string with full Unicode case folding applied.
+    #[cfg(feature = "case-folding")]
+    pub fn case_fold(&self) -> RipString {
+        self.case_converted(Case::Fold)
+    }
+
+    /// Compares both strings under full Unicode case folding.
+    #[cfg(feature = "case-folding")]
+    pub fn eq_ignore_case(&self, other: &RipString) -> bool {
+        self.chars().default_caseless_match(other.chars())
+    }
+
+    #[cfg(feature = "case-folding")]
+    pub fn eq_ignore_case_str(&self, other: &str) -> bool {
+        self.chars().default_caseless_match(other.chars())
+    }
+
+    fn case_converted(&self, case: Case) -> RipString {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            push_converted(
+                &mut nodes,
+                case.convert(node.tp(), self.segmenter),
+                self.segmenter,
+            );
+        }
+        let mut converted = RipString::with_segmenter("", self.segmenter);
+        converted.reset_nodes(nodes);
+        converted
+    }
+
+    /// Converts the units of the range segment by segment. Ascii segments are mapped in
+    /// place, others are split at the range bounds and their converted part is split
+    /// again if the mapping changes its classification.
+    fn convert_case(&mut self, case: Case, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.find_segment(range.start);
+        let mut last = first;
+        while last < self.nodes.len() && self.nodes[last].index() < range.end {
+            last += 1;
+        }
+
+        let base = self.nodes[first].index();
+        let mut nodes = Vec::with_capacity(last - first);
+        for node in self.nodes.drain(first..last) {
+            let start = range.start.saturating_sub(node.index());
+            let end = min(range.end - node.index(), node.len());
+            let mut tp = node.into_tp();
+            if let SegmentType::Ascii(val) = &mut tp {
+                case.map_ascii(&mut val[start..end]);
+                push_segment(&mut nodes, tp);
+                continue;
+            }
+
+            let after = tp.split(end);
+            let middle = tp.split(start);
+            push_segment(&mut nodes, tp);
+            match case.convert(&middle, self.segmenter) {
+                Ok(tp) => push_segment(&mut nodes, tp),
+                Err(text) => {
+                    for tp in Splitter::new(&text, self.segmenter) {
+                        push_segment(&mut nodes, tp);
+                    }
+                }
+            }
+            push_segment(&mut nodes, after);
+        }
+        if nodes.is_empty() {
+            nodes.push(Segment::default());
+        }
+        nodes[0].set_index(base);
+        self.nodes.splice(first..first, nodes);
+        self.finish_edit(first);
+    }
+}
+
+fn push_converted(
+    nodes: &mut Vec<Segment>,
+    converted: Result<SegmentType, String>,
+    segmenter: &dyn Segmenter,
+) {
+    match converted {
+        Ok(tp) => nodes.push(Segment::new(0, tp)),
+        Err(text) => nodes.extend(Splitter::new(&text, segmenter).map(|tp| Segment::new(0, tp))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_case() {
+        let a = RipString::from("Hello, Привет 🏡 straße");
+        assert_eq!(a.to_uppercase().to_string(), "HELLO, ПРИВЕТ 🏡 STRASSE");
+        assert_eq!(a.to_lowercase().to_string(), "hello, привет 🏡 straße");
+        assert_eq!(a.to_string(), "Hello, Привет 🏡 straße");
+
+        let mut a = RipString::from("Hello, Привет 🏡 straße");
+        a.make_uppercase();
+        assert_eq!(a.to_string(), "HELLO, ПРИВЕТ 🏡 STRASSE");
+        assert_eq!(a.len(), 23);
+        a.edit(23..23, "!");
+        a.make_lowercase();
+        assert_eq!(a.to_string(), "hello, привет 🏡 strasse!");
+    }
+
+    #[test]
+    fn test_case_in_place() {
+        let mut a = RipString::from("hello, привет 🏡 straße e\u{301}");
+        a.to_uppercase_in_place(3..10);
+        assert_eq!(a.to_string(), "helLO, ПРИвет 🏡 straße e\u{301}");
+        a.to_uppercase_in_place(16..);
+        assert_eq!(a.to_string(), "helLO, ПРИвет 🏡 STRASSE E\u{301}");
+        assert_eq!(a.len(), 25);
+        a.to_lowercase_in_place(..10);
+        assert_eq!(a.to_string(), "hello, привет 🏡 STRASSE E\u{301}");
+        assert_eq!(a.validate(), Ok(()));
+
+        let text = "abc Привет ".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.to_uppercase_in_place(1000..2000);
+        let mut expected = text.chars().collect::<Vec<_>>();
+        for ch in &mut expected[1000..2000] {
+            *ch = ch.to_uppercase().next().unwrap();
+        }
+        assert_eq!(a.to_string(), expected.into_iter().collect::<String>());
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "case-folding")]
+    fn test_case_fold() {
+        let a = RipString::from("Straße ΣΊΣΥΦΟΣ");
+        assert_eq!(a.case_fold().to_string(), "strasse σίσυφοσ");
+        assert!(a.eq_ignore_case(&RipString::from("STRASSE σίσυφος")));
+        assert!(a.eq_ignore_case_str("strasse σίσυφος"));
+        assert!(!a.eq_ignore_case_str("strase σίσυφος"));
+    }
+
+    #[test]
+    fn test_titlecase() {
+        let mut a = RipString::from("hello WORLD, ǆungla ﬁx o'neil мИР");
+        a.to_titlecase(..);
+        assert_eq!(a.to_string(), "Hello World, ǅungla Fix O'Neil Мир");
+
+        let mut a = RipString::from("hello WORLD again");
+        a.to_titlecase(2..11);
+        assert_eq!(a.to_string(), "hello World again");
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 100 is out of bounds of length 17")]
+    fn test_titlecase_out_of_bounds() {
+        RipString::from("hello WORLD again").to_titlecase(0..100);
+    }
+
+    #[test]
+    fn test_capitalize_sentences() {
+        let mut a =
+            RipString::from("first one. second?  «third» 4 apples! done.\nnext \"line.\" ok");
+        a.capitalize_sentences(0..a.len());
+        assert_eq!(
+            a.to_string(),
+            "First one. Second?  «Third» 4 apples! Done.\nNext \"line.\" Ok"
+        );
+
+        let mut a = RipString::from("one. two. three");
+        a.capitalize_sentences(5..a.len());
+        assert_eq!(a.to_string(), "one. Two. Three");
+        a.capitalize_sentences(1..3);
+        assert_eq!(a.to_string(), "one. Two. Three");
+    }
+
+    #[test]
+    #[should_panic(expected = "Range starts at 3 after its end 1")]
+    fn test_capitalize_inverted_range() {
+        let (start, end) = (3, 1);
+        RipString::from("one. two").capitalize_sentences(start..end);
+    }
+}
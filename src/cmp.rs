@@ -0,0 +1,201 @@
+use crate::segment::{Segment, SegmentType};
+use crate::RipString;
+use alloc::string::String;
+use core::cmp::min;
+use core::slice;
+
+/// Piece of the text as stored in a segment.
+#[derive(Clone, Copy)]
+enum Piece<'a> {
+    Bytes(&'a [u8]),
+    Char(char),
+}
+
+impl<'a> Piece<'a> {
+    fn bytes<'b>(self, buf: &'b mut [u8; 4]) -> &'b [u8]
+    where
+        'a: 'b,
+    {
+        match self {
+            Piece::Bytes(bytes) => bytes,
+            Piece::Char(ch) => ch.encode_utf8(buf).as_bytes(),
+        }
+    }
+}
+
+/// Iterator over the pieces of all segments in text order: whole ASCII segments, chars
+/// and grapheme clusters.
+struct Pieces<'a> {
+    nodes: slice::Iter<'a, Segment>,
+    chars: slice::Iter<'a, char>,
+    graphemes: slice::Iter<'a, String>,
+}
+
+impl<'a> Pieces<'a> {
+    fn new(rip: &'a RipString) -> Pieces<'a> {
+        Pieces {
+            nodes: rip.nodes.iter(),
+            chars: [].iter(),
+            graphemes: [].iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for Pieces<'a> {
+    type Item = Piece<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ch) = self.chars.next() {
+                return Some(Piece::Char(*ch));
+            }
+            if let Some(grapheme) = self.graphemes.next() {
+                return Some(Piece::Bytes(grapheme.as_bytes()));
+            }
+            match self.nodes.next()?.tp() {
+                SegmentType::Ascii(val) if !val.is_empty() => return Some(Piece::Bytes(val)),
+                SegmentType::Ascii(_) => {}
+                SegmentType::Utf8(val) => self.chars = val.iter(),
+                SegmentType::Unicode(val) => self.graphemes = val.iter(),
+            }
+        }
+    }
+}
+
+/// Removes `prefix` from the front of `rest` if it starts with it.
+fn strip(rest: &mut &[u8], prefix: &[u8]) -> bool {
+    match rest.strip_prefix(prefix) {
+        Some(tail) => {
+            *rest = tail;
+            true
+        }
+        None => false,
+    }
+}
+
+impl RipString {
+    /// Compares the text with `other` piece by piece without allocating.
+    fn eq_bytes(&self, other: &[u8]) -> bool {
+        let mut rest = other;
+        let mut buf = [0; 4];
+        Pieces::new(self).all(|piece| strip(&mut rest, piece.bytes(&mut buf))) && rest.is_empty()
+    }
+}
+
+/// Compares the text of both strings, walking their pieces in lockstep regardless of
+/// where the segments end.
+impl PartialEq for RipString {
+    fn eq(&self, other: &RipString) -> bool {
+        let (mut a, mut b) = (Pieces::new(self), Pieces::new(other));
+        let (mut buf_a, mut buf_b) = ([0; 4], [0; 4]);
+        let (mut piece_a, mut piece_b) = (a.next(), b.next());
+        let (mut pos_a, mut pos_b) = (0, 0);
+        loop {
+            let (bytes_a, bytes_b) = match (piece_a, piece_b) {
+                (Some(x), Some(y)) => (x.bytes(&mut buf_a), y.bytes(&mut buf_b)),
+                (None, None) => return true,
+                _ => return false,
+            };
+            let len = min(bytes_a.len() - pos_a, bytes_b.len() - pos_b);
+            if bytes_a[pos_a..pos_a + len] != bytes_b[pos_b..pos_b + len] {
+                return false;
+            }
+            pos_a += len;
+            pos_b += len;
+            if pos_a == bytes_a.len() {
+                piece_a = a.next();
+                pos_a = 0;
+            }
+            if pos_b == bytes_b.len() {
+                piece_b = b.next();
+                pos_b = 0;
+            }
+        }
+    }
+}
+
+impl Eq for RipString {}
+
+impl PartialEq<str> for RipString {
+    fn eq(&self, other: &str) -> bool {
+        self.eq_bytes(other.as_bytes())
+    }
+}
+
+impl PartialEq<&str> for RipString {
+    fn eq(&self, other: &&str) -> bool {
+        self.eq_bytes(other.as_bytes())
+    }
+}
+
+impl PartialEq<String> for RipString {
+    fn eq(&self, other: &String) -> bool {
+        self.eq_bytes(other.as_bytes())
+    }
+}
+
+impl PartialEq<RipString> for str {
+    fn eq(&self, other: &RipString) -> bool {
+        other.eq_bytes(self.as_bytes())
+    }
+}
+
+impl PartialEq<RipString> for &str {
+    fn eq(&self, other: &RipString) -> bool {
+        other.eq_bytes(self.as_bytes())
+    }
+}
+
+impl PartialEq<RipString> for String {
+    fn eq(&self, other: &RipString) -> bool {
+        other.eq_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::String;
+
+    #[test]
+    fn test_eq_str() {
+        let mut a = RipString::from("Hello, мир 🏡 e\u{301}\r\n");
+        assert_eq!(a, "Hello, мир 🏡 e\u{301}\r\n");
+        assert_eq!(*"Hello, мир 🏡 e\u{301}\r\n", a);
+        assert_eq!(String::from("Hello, мир 🏡 e\u{301}\r\n"), a);
+        assert_ne!(a, "Hello, мир 🏡 e\u{301}\r");
+        assert_ne!(a, "Hello, мир 🏡 e\u{301}\r\n!");
+        assert_ne!("Hello, мир 🏡 e\r\n", a);
+
+        a.clear();
+        assert_eq!(a, "");
+        assert_ne!(a, String::from("a"));
+
+        let text = "ab мир 🏡\n".repeat(300);
+        let a = RipString::from(text.as_str());
+        assert_eq!(a, text);
+        assert_ne!(a, text.replacen("мир", "мор", 1));
+    }
+
+    #[test]
+    fn test_eq() {
+        let text = "ab мир 🏡 e\u{301}\n".repeat(200);
+        let a = RipString::from(text.as_str());
+        let mut b = RipString::new();
+        for part in text.split_inclusive(' ') {
+            b.push_str(part);
+        }
+        b.insert(5, "x");
+        assert_ne!(a, b);
+        b.remove(5..6);
+        assert_eq!(a, b);
+        assert_eq!(b, a);
+        assert_ne!(a.dump_structure(), b.dump_structure());
+
+        b.push('!');
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+        assert_eq!(RipString::new(), RipString::from(""));
+        assert_ne!(RipString::new(), RipString::from("a"));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::RipString;
+#[cfg(feature = "width")]
+use unicode_width::UnicodeWidthStr;
+
+#[cfg(feature = "width")]
+pub(crate) fn unit_width(unit: &str) -> usize {
+    unit.width()
+}
+
+#[cfg(not(feature = "width"))]
+pub(crate) fn unit_width(_unit: &str) -> usize {
+    1
+}
+
+/// Returns the column following `unit` displayed at `col`.
+fn advance(col: usize, unit: &str, tab_width: usize) -> usize {
+    if unit != "\t" {
+        col + unit_width(unit)
+    } else {
+        col.checked_div(tab_width)
+            .map_or(col, |tabs| (tabs + 1) * tab_width)
+    }
+}
+
+impl RipString {
+    /// Returns the index of the unit displayed at `col` of the given line, with tabs
+    /// expanded to `tab_width` columns. Columns past the end of the line map to its end.
+    pub fn visual_col_to_index(&self, line: usize, col: usize, tab_width: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let mut current = 0;
+        let mut index = range.start;
+        let mut found = None;
+        self.for_each_unit(range.clone(), |unit| {
+            if found.is_none() {
+                current = advance(current, unit, tab_width);
+                if col < current {
+                    found = Some(index);
+                }
+                index += 1;
+            }
+        });
+        Some(found.unwrap_or(range.end))
+    }
+
+    /// Returns the display column of the given index, with tabs expanded to `tab_width` columns.
+    pub fn index_to_visual_col_with_tabs(&self, index: usize, tab_width: usize) -> usize {
+        let start = self
+            .line_to_index(self.index_to_line(index))
+            .unwrap_or_default();
+        let mut col = 0;
+        self.for_each_unit(start..index, |unit| col = advance(col, unit, tab_width));
+        col
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+
+    #[test]
+    fn test_tab_columns() {
+        let a = RipString::from("x\n\tab\tc\n");
+        assert_eq!(a.visual_col_to_index(1, 0, 4), Some(2));
+        assert_eq!(a.visual_col_to_index(1, 3, 4), Some(2));
+        assert_eq!(a.visual_col_to_index(1, 4, 4), Some(3));
+        assert_eq!(a.visual_col_to_index(1, 7, 4), Some(5));
+        assert_eq!(a.visual_col_to_index(1, 8, 4), Some(6));
+        assert_eq!(a.visual_col_to_index(1, 20, 4), Some(7));
+        assert_eq!(a.visual_col_to_index(2, 1, 4), Some(8));
+        assert_eq!(a.visual_col_to_index(3, 0, 4), None);
+
+        assert_eq!(a.index_to_visual_col_with_tabs(2, 4), 0);
+        assert_eq!(a.index_to_visual_col_with_tabs(3, 4), 4);
+        assert_eq!(a.index_to_visual_col_with_tabs(6, 4), 8);
+        assert_eq!(a.index_to_visual_col_with_tabs(6, 8), 16);
+        assert_eq!(a.index_to_visual_col_with_tabs(6, 0), 2);
+    }
+}
@@ -0,0 +1,7 @@
+//! Facades mirroring the APIs of other rope crates to ease migration.
+
+pub mod ropey;
+#[cfg(feature = "text-size")]
+mod text_size;
+#[cfg(feature = "xi")]
+mod xi;
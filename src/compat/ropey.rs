@@ -0,0 +1,327 @@
+//! A subset of the [ropey](https://docs.rs/ropey) API backed by `RipString`.
+//!
+//! Indices are chars as in ropey. Since the text is edited in grapheme clusters, a char
+//! index inside a cluster is moved to the start of the cluster.
+
+use crate::iter::Chars;
+use crate::segment::SegmentType;
+use crate::RipString;
+use alloc::fmt::{Display, Formatter};
+use core::ops::{Bound, Range, RangeBounds};
+
+pub struct Rope {
+    rip: RipString,
+}
+
+impl Rope {
+    pub fn new() -> Rope {
+        Rope {
+            rip: RipString::new(),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Rope {
+        Rope {
+            rip: RipString::from(text),
+        }
+    }
+
+    pub fn into_inner(self) -> RipString {
+        self.rip
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.rip.len_bytes()
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.rip
+            .nodes
+            .iter()
+            .map(|node| node.tp().char_count())
+            .sum()
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.rip.line_count()
+    }
+
+    pub fn char(&self, char_idx: usize) -> char {
+        self.chars().nth(char_idx).expect("Index is out of bound")
+    }
+
+    pub fn chars(&self) -> Chars<'_> {
+        self.rip.chars()
+    }
+
+    pub fn char_to_line(&self, char_idx: usize) -> usize {
+        self.rip.index_to_line(self.char_to_unit(char_idx))
+    }
+
+    pub fn line_to_char(&self, line_idx: usize) -> usize {
+        match self.rip.line_to_index(line_idx) {
+            Some(index) => self.unit_to_char(index),
+            None if line_idx == self.len_lines() => self.len_chars(),
+            None => panic!("Index is out of bound"),
+        }
+    }
+
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        self.check_char(char_idx);
+        let mut chars = 0;
+        let mut bytes = 0;
+        for node in &self.rip.nodes {
+            let count = node.tp().char_count();
+            if chars + count > char_idx {
+                return bytes
+                    + node
+                        .chars()
+                        .take(char_idx - chars)
+                        .map(char::len_utf8)
+                        .sum::<usize>();
+            }
+            chars += count;
+            bytes += node.byte_len();
+        }
+        bytes
+    }
+
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        assert!(byte_idx <= self.len_bytes(), "Index is out of bound");
+        let mut chars = 0;
+        let mut bytes = 0;
+        for node in &self.rip.nodes {
+            let len = node.byte_len();
+            if bytes + len > byte_idx {
+                for ch in node.chars() {
+                    bytes += ch.len_utf8();
+                    if bytes > byte_idx {
+                        break;
+                    }
+                    chars += 1;
+                }
+                return chars;
+            }
+            chars += node.tp().char_count();
+            bytes += len;
+        }
+        chars
+    }
+
+    pub fn insert(&mut self, char_idx: usize, text: &str) {
+        let index = self.char_to_unit(char_idx);
+        self.rip.apply_edits(&[(index..index, text)]);
+    }
+
+    pub fn insert_char(&mut self, char_idx: usize, ch: char) {
+        let mut buf = [0; 4];
+        self.insert(char_idx, ch.encode_utf8(&mut buf));
+    }
+
+    pub fn remove<R: RangeBounds<usize>>(&mut self, char_range: R) {
+        let range = self.unit_range(char_range);
+        self.rip.apply_edits(&[(range, "")]);
+    }
+
+    pub fn slice<R: RangeBounds<usize>>(&self, char_range: R) -> RopeSlice<'_> {
+        RopeSlice {
+            rip: &self.rip,
+            range: self.unit_range(char_range),
+        }
+    }
+
+    /// Returns the line with its line break.
+    pub fn line(&self, line_idx: usize) -> RopeSlice<'_> {
+        let start = self
+            .rip
+            .line_to_index(line_idx)
+            .expect("Index is out of bound");
+        let end = match self.rip.line_end(start) {
+            (_, Some(next)) => next,
+            (end, None) => end,
+        };
+        RopeSlice {
+            rip: &self.rip,
+            range: start..end,
+        }
+    }
+
+    fn check_char(&self, char_idx: usize) {
+        assert!(char_idx <= self.len_chars(), "Index is out of bound");
+    }
+
+    /// Returns the index of the unit containing the char.
+    fn char_to_unit(&self, char_idx: usize) -> usize {
+        self.check_char(char_idx);
+        let mut chars = 0;
+        for node in &self.rip.nodes {
+            let count = node.tp().char_count();
+            if chars + count > char_idx {
+                let offset = char_idx - chars;
+                return match node.tp() {
+                    SegmentType::Unicode(val) => {
+                        let mut unit = 0;
+                        for grapheme in val {
+                            chars += grapheme.chars().count();
+                            if chars > char_idx {
+                                break;
+                            }
+                            unit += 1;
+                        }
+                        node.index() + unit
+                    }
+                    _ => node.index() + offset,
+                };
+            }
+            chars += count;
+        }
+        self.rip.len()
+    }
+
+    fn unit_to_char(&self, index: usize) -> usize {
+        let mut chars = 0;
+        for node in &self.rip.nodes {
+            if node.index() + node.len() > index {
+                let offset = index - node.index();
+                return chars
+                    + match node.tp() {
+                        SegmentType::Unicode(val) => {
+                            val[..offset].iter().map(|g| g.chars().count()).sum()
+                        }
+                        _ => offset,
+                    };
+            }
+            chars += node.tp().char_count();
+        }
+        chars
+    }
+
+    fn unit_range<R: RangeBounds<usize>>(&self, char_range: R) -> Range<usize> {
+        let start = match char_range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match char_range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.len_chars(),
+        };
+        assert!(start <= end, "Range is inverted");
+        self.char_to_unit(start)..self.char_to_unit(end)
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Rope::from_str(text)
+    }
+}
+
+impl From<RipString> for Rope {
+    fn from(rip: RipString) -> Self {
+        Rope { rip }
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.rip.fmt(f)
+    }
+}
+
+pub struct RopeSlice<'a> {
+    rip: &'a RipString,
+    range: Range<usize>,
+}
+
+impl<'a> RopeSlice<'a> {
+    pub fn len_chars(&self) -> usize {
+        let mut chars = 0;
+        self.rip
+            .for_each_unit(self.range.clone(), |unit| chars += unit.chars().count());
+        chars
+    }
+}
+
+impl<'a> Display for RopeSlice<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut result = Ok(());
+        self.rip.for_each_unit(self.range.clone(), |unit| {
+            if result.is_ok() {
+                result = f.write_str(unit);
+            }
+        });
+        result
+    }
+}
+
+/// Builds a rope from chunks of text.
+#[derive(Default)]
+pub struct RopeBuilder {
+    builder: crate::RopeBuilder,
+}
+
+impl RopeBuilder {
+    pub fn new() -> RopeBuilder {
+        RopeBuilder::default()
+    }
+
+    pub fn append(&mut self, chunk: &str) {
+        self.builder.append(chunk);
+    }
+
+    pub fn finish(self) -> Rope {
+        Rope {
+            rip: self.builder.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compat::ropey::RopeBuilder;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_rope() {
+        let mut builder = RopeBuilder::new();
+        builder.append("Hello\r\nмир ");
+        builder.append("🏡 e\u{301}!\nend");
+        let mut rope = builder.finish();
+        assert_eq!(rope.len_chars(), 20);
+        assert_eq!(rope.len_bytes(), 27);
+        assert_eq!(rope.len_lines(), 3);
+        assert_eq!(rope.char(8), 'и');
+
+        assert_eq!(rope.char_to_line(6), 0);
+        assert_eq!(rope.char_to_line(7), 1);
+        assert_eq!(rope.char_to_line(20), 2);
+        assert_eq!(rope.line_to_char(2), 17);
+        assert_eq!(rope.line_to_char(3), 20);
+        assert_eq!(rope.char_to_byte(12), 18);
+        assert_eq!(rope.char_to_byte(14), 20);
+        assert_eq!(rope.byte_to_char(18), 12);
+        assert_eq!(rope.byte_to_char(20), 14);
+
+        assert_eq!(rope.line(1).to_string(), "мир 🏡 e\u{301}!\n");
+        assert_eq!(rope.line(1).len_chars(), 10);
+        assert_eq!(rope.slice(7..=9).to_string(), "мир");
+        assert_eq!(rope.slice(15..).to_string(), "!\nend");
+
+        rope.insert(15, "?");
+        rope.insert_char(0, '>');
+        rope.remove(1..8);
+        assert_eq!(rope.to_string(), ">мир 🏡 e\u{301}?!\nend");
+        // Char 8 is inside the cluster "e\u{301}", so the range ends at its start.
+        rope.remove(6..8);
+        assert_eq!(rope.to_string(), ">мир 🏡e\u{301}?!\nend");
+    }
+}
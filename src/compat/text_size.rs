@@ -0,0 +1,123 @@
+//! Conversions to the [text-size](https://docs.rs/text-size) offsets used by rowan and
+//! rust-analyzer.
+//!
+//! `TextSize` and `TextRange` are byte offsets. Offsets inside a unit are moved to its
+//! start.
+
+use crate::{RipError, RipString};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::ops::Range;
+use text_size::{TextRange, TextSize};
+
+impl RipString {
+    /// Returns the byte offset of the index. Panics if it does not fit in `u32`.
+    pub fn index_to_text_size(&self, index: usize) -> TextSize {
+        TextSize::try_from(self.index_to_byte(index)).expect("Offset does not fit in TextSize")
+    }
+
+    pub fn text_size_to_index(&self, offset: TextSize) -> usize {
+        self.byte_to_index(offset.into())
+    }
+
+    pub fn range_to_text_range(&self, range: Range<usize>) -> TextRange {
+        TextRange::new(
+            self.index_to_text_size(range.start),
+            self.index_to_text_size(range.end),
+        )
+    }
+
+    pub fn text_range_to_range(&self, range: TextRange) -> Range<usize> {
+        self.text_size_to_index(range.start())..self.text_size_to_index(range.end())
+    }
+
+    /// Converts an edit list against this text to rowan-style `(TextRange, String)`
+    /// deletions and insertions, as taken by incremental reparsing.
+    pub fn edits_to_text_edits<S: AsRef<str>>(
+        &self,
+        edits: &[(Range<usize>, S)],
+    ) -> Vec<(TextRange, String)> {
+        edits
+            .iter()
+            .map(|(range, text)| {
+                (
+                    self.range_to_text_range(range.clone()),
+                    String::from(text.as_ref()),
+                )
+            })
+            .collect()
+    }
+
+    /// Applies non-overlapping rowan-style edits against this text, in any order, see
+    /// `edit_all`. A range past the end is reported in bytes; on error the text is left
+    /// unchanged.
+    pub fn apply_text_edits<S: AsRef<str>>(
+        &mut self,
+        edits: &[(TextRange, S)],
+    ) -> Result<(), RipError> {
+        let len = self.len_bytes();
+        let edits = edits
+            .iter()
+            .map(|(range, text)| {
+                let end = usize::from(range.end());
+                if end > len {
+                    return Err(RipError::OutOfBounds { index: end, len });
+                }
+                Ok((self.text_range_to_range(*range), text.as_ref()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.edit_all(&edits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipError, RipString};
+    use alloc::string::ToString;
+    use text_size::{TextRange, TextSize};
+
+    #[test]
+    fn test_text_size() {
+        let mut a = RipString::from("fn мир() {}");
+        assert_eq!(a.index_to_text_size(4), TextSize::from(5));
+        assert_eq!(a.text_size_to_index(TextSize::from(6)), 4);
+        assert_eq!(
+            a.range_to_text_range(3..6),
+            TextRange::new(3.into(), 9.into())
+        );
+        assert_eq!(
+            a.text_range_to_range(TextRange::new(3.into(), 9.into())),
+            3..6
+        );
+
+        let edits = a.edits_to_text_edits(&[(3..6, "main"), (10..10, " 1 ")]);
+        assert_eq!(
+            edits[0],
+            (TextRange::new(3.into(), 9.into()), "main".to_string())
+        );
+        assert_eq!(edits[1].0, TextRange::empty(13.into()));
+        a.apply_text_edits(&edits).unwrap();
+        assert_eq!(a.to_string(), "fn main() { 1 }");
+    }
+
+    #[test]
+    fn test_apply_text_edits_checked() {
+        let range = |start: u32, end: u32| TextRange::new(start.into(), end.into());
+        let mut a = RipString::from("fn main() {}");
+        a.apply_text_edits(&[(range(10, 11), "X"), (range(0, 2), "pub fn")])
+            .unwrap();
+        assert_eq!(a.to_string(), "pub fn main() X}");
+
+        let mut a = RipString::from("fn main() {}");
+        assert_eq!(
+            a.apply_text_edits(&[(range(0, 4), "A"), (range(2, 5), "B")]),
+            Err(RipError::OverlappingEdits(2))
+        );
+        assert_eq!(
+            a.apply_text_edits(&[(range(0, 2), "A"), (range(10, 20), "B")]),
+            Err(RipError::OutOfBounds { index: 20, len: 12 })
+        );
+        assert_eq!(a.to_string(), "fn main() {}");
+    }
+}
@@ -0,0 +1,93 @@
+//! Conversions between edit lists and [xi-rope](https://docs.rs/xi-rope) deltas.
+//!
+//! Edit lists are sorted, non-overlapping `(range, text)` pairs in unit indices, as taken
+//! by the batch operations of `RipString`. Delta offsets are bytes.
+
+use crate::RipString;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+use core::ops::Range;
+use xi_rope::{DeltaBuilder, DeltaElement, Interval, Rope, RopeDelta};
+
+impl RipString {
+    /// Converts a delta against this text to an edit list. Offsets inside a unit are
+    /// moved to its start.
+    pub fn delta_to_edits(&self, delta: &RopeDelta) -> Vec<(Range<usize>, String)> {
+        let mut edits = Vec::new();
+        let mut copied = 0;
+        let mut inserted = String::new();
+        for el in &delta.els {
+            match el {
+                DeltaElement::Copy(start, end) => {
+                    assert!(*start >= copied, "Delta copies are out of order");
+                    if *start > copied || !inserted.is_empty() {
+                        edits.push((copied..*start, mem::take(&mut inserted)));
+                    }
+                    copied = *end;
+                }
+                DeltaElement::Insert(node) => inserted.push_str(&String::from(node)),
+            }
+        }
+        if delta.base_len > copied || !inserted.is_empty() {
+            edits.push((copied..delta.base_len, inserted));
+        }
+
+        for (range, _) in &mut edits {
+            *range = self.byte_to_index(range.start)..self.byte_to_index(range.end);
+        }
+        edits
+    }
+
+    /// Converts an edit list against this text to a delta.
+    pub fn edits_to_delta<S: AsRef<str>>(&self, edits: &[(Range<usize>, S)]) -> RopeDelta {
+        let mut builder = DeltaBuilder::new(self.index_to_byte(self.len()));
+        for (range, text) in edits {
+            let interval = Interval::new(
+                self.index_to_byte(range.start),
+                self.index_to_byte(range.end),
+            );
+            builder.replace(interval, Rope::from(text.as_ref()));
+        }
+        builder.build()
+    }
+
+    /// Applies a delta against this text.
+    pub fn apply_delta(&mut self, delta: &RopeDelta) {
+        let edits = self.delta_to_edits(delta);
+        self.apply_edits(&edits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::{String, ToString};
+    use xi_rope::{Rope, Transformer};
+
+    #[test]
+    fn test_delta() {
+        let text = "Hello мир 🏡!";
+        let mut a = RipString::from(text);
+        let edits = [(0..5, "Bye"), (6..6, "big "), (11..12, "")];
+        let delta = a.edits_to_delta(&edits);
+        assert_eq!(
+            String::from(delta.apply(&Rope::from(text))),
+            "Bye big мир 🏡"
+        );
+        assert_eq!(Transformer::new(&delta).transform(6, false), 4);
+
+        let back = a.delta_to_edits(&delta);
+        assert_eq!(
+            back,
+            [
+                (0..5, "Bye".to_string()),
+                (6..6, "big ".to_string()),
+                (11..12, String::new())
+            ]
+        );
+
+        a.apply_delta(&delta);
+        assert_eq!(a.to_string(), "Bye big мир 🏡");
+    }
+}
@@ -0,0 +1,125 @@
+use crate::segment::Segment;
+use crate::splitter::{SegmentKind, MAX_BLOCK_SIZE};
+use crate::RipString;
+use alloc::string::String;
+use core::cmp::min;
+use core::fmt::{self, Debug, Formatter, Write};
+
+/// Width of the fill bar in characters.
+const BAR_WIDTH: usize = 16;
+/// Number of units shown of every segment by `Debug`.
+const PREVIEW_UNITS: usize = 16;
+
+/// Debug view of a segment: its index range, kind, length and the start of its text.
+struct SegmentSummary<'a>(&'a Segment);
+
+impl Debug for SegmentSummary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let node = self.0;
+        let mut preview = String::new();
+        node.for_each_unit(0..min(node.len(), PREVIEW_UNITS), |unit| {
+            preview.push_str(unit)
+        });
+        write!(
+            f,
+            "{}..{} {:?} {} {:?}",
+            node.index(),
+            node.index() + node.len(),
+            node.tp().kind(),
+            node.len(),
+            preview
+        )?;
+        if node.len() > PREVIEW_UNITS {
+            f.write_str("…")?;
+        }
+        Ok(())
+    }
+}
+
+struct Segments<'a>(&'a [Segment]);
+
+impl Debug for Segments<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(SegmentSummary))
+            .finish()
+    }
+}
+
+impl Debug for RipString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RipString")
+            .field("len", &self.len())
+            .field("last_edit", &self.last_edit)
+            .field("segments", &Segments(&self.nodes))
+            .finish()
+    }
+}
+
+impl RipString {
+    /// Renders the segments as a table with their index, length, kind and how full they
+    /// are relative to the maximum block size. The last edited segment is marked with `*`.
+    pub fn dump_structure(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{} segments, {} units", self.nodes.len(), self.len());
+        for (i, node) in self.nodes.iter().enumerate() {
+            let kind = match node.tp().kind() {
+                SegmentKind::Ascii => "ascii",
+                SegmentKind::Utf8 => "utf8",
+                SegmentKind::Unicode => "unicode",
+            };
+            let filled = (node.len() * BAR_WIDTH).div_ceil(MAX_BLOCK_SIZE);
+            let _ = writeln!(
+                out,
+                "{}{:>5} {:>8} {:>5} {:<7} [{:<bar$}] {:>3}%",
+                if i == self.last_edit { '*' } else { ' ' },
+                i,
+                node.index(),
+                node.len(),
+                kind,
+                "#".repeat(filled),
+                node.len() * 100 / MAX_BLOCK_SIZE,
+                bar = BAR_WIDTH,
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::format;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_dump_structure() {
+        let mut a = RipString::from("Hello мир");
+        a.edit(9..9, &"!".repeat(600));
+        assert_eq!(
+            a.dump_structure().lines().collect::<Vec<_>>(),
+            [
+                "3 segments, 609 units",
+                "     0        0     6 ascii   [#               ]   0%",
+                "*    1        6     3 utf8    [#               ]   0%",
+                "     2        9   600 ascii   [##########      ]  58%",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut a = RipString::from("Hello мир");
+        a.edit(9..9, &"!".repeat(600));
+        assert_eq!(
+            format!("{:?}", a),
+            "RipString { len: 609, last_edit: 1, segments: [0..6 Ascii 6 \"Hello \", \
+             6..9 Utf8 3 \"мир\", 9..609 Ascii 600 \"!!!!!!!!!!!!!!!!\"…] }"
+        );
+        assert_eq!(
+            format!("{:?}", RipString::from("e\u{301}\n")),
+            "RipString { len: 2, last_edit: 0, segments: \
+             [0..1 Unicode 1 \"e\\u{301}\", 1..2 Ascii 1 \"\\n\"] }"
+        );
+    }
+}
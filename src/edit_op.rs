@@ -0,0 +1,52 @@
+use crate::RipString;
+use alloc::string::String;
+use core::cmp::min;
+use core::ops::Range;
+
+/// Pieces of text that exercise the different segment types and cluster boundaries.
+pub(crate) const FRAGMENTS: &[&str] = &[
+    "a",
+    "Z",
+    "0",
+    " ",
+    "\t",
+    "\r",
+    "\n",
+    "\r\n",
+    "é",
+    "e\u{301}",
+    "\u{301}",
+    "мир",
+    "🏡",
+    "👨\u{200d}👩\u{200d}👧",
+    "\u{200d}",
+    "🇺🇦",
+    "नमस्ते",
+];
+
+/// An edit whose indices are reduced to the bounds of the text it is applied to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOp {
+    Insert { index: usize, text: String },
+    Remove { range: Range<usize> },
+    Replace { range: Range<usize>, text: String },
+}
+
+impl EditOp {
+    /// Returns the range and text of the edit for a text of `len` units.
+    pub fn resolve(&self, len: usize) -> (Range<usize>, &str) {
+        let (range, text) = match self {
+            EditOp::Insert { index, text } => (*index..*index, text.as_str()),
+            EditOp::Remove { range } => (range.clone(), ""),
+            EditOp::Replace { range, text } => (range.clone(), text.as_str()),
+        };
+        let start = range.start % (len + 1);
+        let end = start + min(range.end.saturating_sub(range.start), len - start);
+        (start..end, text)
+    }
+
+    pub fn apply(&self, rope: &mut RipString) {
+        let (range, text) = self.resolve(rope.len());
+        rope.edit(range, text);
+    }
+}
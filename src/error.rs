@@ -0,0 +1,96 @@
+use alloc::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
+pub enum RipError {
+    /// Edit boundary falls inside a grapheme cluster.
+    SplitsGrapheme(usize),
+    /// UTF-16 input has an unpaired surrogate at the offset in code units.
+    UnpairedSurrogate(usize),
+    /// Byte input is not valid UTF-8 at the offset.
+    InvalidUtf8(usize),
+    /// Index is past the end of the text of the given length.
+    OutOfBounds { index: usize, len: usize },
+    /// Range starts after its end.
+    InvertedRange { start: usize, end: usize },
+    /// Two ranges of a batch of edits overlap at the index.
+    OverlappingEdits(usize),
+    /// Snapshot data is malformed at the byte offset.
+    InvalidSnapshot(usize),
+}
+
+impl Display for RipError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RipError::SplitsGrapheme(index) => {
+                write!(f, "Index {} is inside a grapheme cluster", index)
+            }
+            RipError::UnpairedSurrogate(offset) => {
+                write!(f, "Unpaired surrogate at UTF-16 offset {}", offset)
+            }
+            RipError::InvalidUtf8(offset) => write!(f, "Invalid UTF-8 at byte {}", offset),
+            RipError::OutOfBounds { index, len } => {
+                write!(f, "Index {} is out of bounds of length {}", index, len)
+            }
+            RipError::InvertedRange { start, end } => {
+                write!(f, "Range starts at {} after its end {}", start, end)
+            }
+            RipError::OverlappingEdits(index) => write!(f, "Edits overlap at index {}", index),
+            RipError::InvalidSnapshot(offset) => write!(f, "Invalid snapshot at byte {}", offset),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RipError {}
+
+/// Broken structural invariant of a `RipString`, reported by `validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+    /// The string has no segments at all.
+    NoSegments,
+    /// Segment starts at `index` instead of right after the previous one.
+    MisplacedSegment {
+        segment: usize,
+        index: usize,
+        expected: usize,
+    },
+    /// Segment is longer than the maximum block size.
+    OversizedSegment { segment: usize, len: usize },
+    /// Empty segment in a string with several segments.
+    EmptySegment(usize),
+    /// Cached byte offset or byte length of the segment does not match its text.
+    StaleByteOffset(usize),
+    /// The cached last edited segment does not exist.
+    LastEditOutOfBounds(usize),
+}
+
+impl Display for InvariantError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvariantError::NoSegments => write!(f, "No segments"),
+            InvariantError::MisplacedSegment {
+                segment,
+                index,
+                expected,
+            } => write!(
+                f,
+                "Segment {} starts at {} instead of {}",
+                segment, index, expected
+            ),
+            InvariantError::OversizedSegment { segment, len } => {
+                write!(f, "Segment {} has {} units", segment, len)
+            }
+            InvariantError::EmptySegment(segment) => write!(f, "Segment {} is empty", segment),
+            InvariantError::StaleByteOffset(segment) => {
+                write!(f, "Segment {} has a stale byte offset", segment)
+            }
+            InvariantError::LastEditOutOfBounds(segment) => {
+                write!(f, "Last edited segment {} does not exist", segment)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantError {}
@@ -0,0 +1,64 @@
+use crate::RipString;
+use alloc::fmt::{Display, Formatter, Write};
+
+/// Displays the text with invisible characters escaped.
+pub struct Escaped<'a> {
+    rope: &'a RipString,
+}
+
+impl<'a> Display for Escaped<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for ch in self.rope.chars() {
+            match ch {
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                '\\' => f.write_str("\\\\")?,
+                ch if is_invisible(ch) => write!(f, "{}", ch.escape_unicode())?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Control, format, bidi control and non-breaking space characters.
+fn is_invisible(ch: char) -> bool {
+    ch.is_control()
+        || matches!(
+            ch,
+            '\u{a0}'
+                | '\u{ad}'
+                | '\u{61c}'
+                | '\u{180e}'
+                | '\u{2007}'
+                | '\u{200b}'..='\u{200f}'
+                | '\u{202a}'..='\u{202f}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{2066}'..='\u{2069}'
+                | '\u{feff}'
+        )
+}
+
+impl RipString {
+    /// Returns a wrapper that displays the text with control characters, non-breaking
+    /// spaces, zero-width and bidi control characters escaped.
+    pub fn display_escaped(&self) -> Escaped<'_> {
+        Escaped { rope: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_display_escaped() {
+        let a = RipString::from("a\u{a0}b\r\n\t\u{1}👨\u{200d}👩 \u{202e}мир\u{202c}\\");
+        assert_eq!(
+            a.display_escaped().to_string(),
+            "a\\u{a0}b\\r\\n\\t\\u{1}👨\\u{200d}👩 \\u{202e}мир\\u{202c}\\\\"
+        );
+    }
+}
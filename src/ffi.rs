@@ -0,0 +1,93 @@
+//! UniFFI bindings for Kotlin and Swift. Indices are units of the rope.
+
+use crate::RipError;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use std::sync::Mutex;
+
+/// Thread-safe handle to a rope, exported to foreign code as `RipString`.
+#[derive(Debug, uniffi::Object)]
+pub struct RipString {
+    rip: Mutex<crate::RipString>,
+}
+
+#[uniffi::export]
+impl RipString {
+    #[uniffi::constructor]
+    pub fn new(text: String) -> Arc<RipString> {
+        Arc::new(RipString {
+            rip: Mutex::new(crate::RipString::from(text.as_str())),
+        })
+    }
+
+    pub fn length(&self) -> u64 {
+        self.with(|rip| rip.len()) as u64
+    }
+
+    /// Replaces the range. Indices past the end are clamped to it; a range starting after
+    /// its end is an error.
+    pub fn edit(&self, start: u64, end: u64, text: String) -> Result<(), RipError> {
+        self.with(|rip| {
+            let range = rip.clamp(start)..rip.clamp(end);
+            rip.try_edit(range, &text)
+        })
+    }
+
+    pub fn slice(&self, start: u64, end: u64) -> String {
+        self.with(|rip| rip.collect_range(rip.clamp(start)..rip.clamp(end)))
+    }
+
+    pub fn line_count(&self) -> u64 {
+        self.with(|rip| rip.line_count()) as u64
+    }
+
+    pub fn line_start(&self, line: u64) -> Option<u64> {
+        self.with(|rip| rip.line_to_index(line as usize))
+            .map(|index| index as u64)
+    }
+
+    pub fn line_at(&self, index: u64) -> u64 {
+        self.with(|rip| rip.index_to_line(rip.clamp(index))) as u64
+    }
+
+    pub fn text(&self) -> String {
+        self.with(|rip| rip.to_string())
+    }
+}
+
+impl RipString {
+    fn with<R, F: FnOnce(&mut crate::RipString) -> R>(&self, f: F) -> R {
+        let mut rip = self.rip.lock().unwrap_or_else(|err| err.into_inner());
+        f(&mut rip)
+    }
+}
+
+impl crate::RipString {
+    fn clamp(&self, index: u64) -> usize {
+        core::cmp::min(index, self.len() as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ffi::RipString;
+    use crate::RipError;
+
+    #[test]
+    fn test_ffi() {
+        let a = RipString::new("a🏡 мир\nok".into());
+        assert_eq!(a.length(), 9);
+        assert_eq!(a.slice(1, 2), "🏡");
+        assert_eq!(a.line_start(1), Some(7));
+        assert_eq!(a.line_at(8), 1);
+
+        assert_eq!(a.edit(2, 100, "!".into()), Ok(()));
+        assert_eq!(a.text(), "a🏡!");
+        assert_eq!(
+            a.edit(100, 1, "?".into()),
+            Err(RipError::InvertedRange { start: 3, end: 1 })
+        );
+        assert_eq!(a.line_count(), 1);
+        assert_eq!(a.line_start(1), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! Structured inputs for fuzz targets.
+
+use crate::edit_op::FRAGMENTS;
+use crate::{EditOp, RipString};
+use alloc::string::String;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+fn fragments(u: &mut Unstructured<'_>, max: usize) -> Result<String> {
+    let mut text = String::new();
+    for _ in 0..u.int_in_range(0..=max)? {
+        text.push_str(u.choose(FRAGMENTS)?);
+    }
+    Ok(text)
+}
+
+impl<'a> Arbitrary<'a> for RipString {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(RipString::from(fragments(u, 4096)?.as_str()))
+    }
+}
+
+impl<'a> Arbitrary<'a> for EditOp {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let start = u.int_in_range(0..=8192)?;
+        let range = start..start + u.int_in_range(0..=64)?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => EditOp::Insert {
+                index: start,
+                text: fragments(u, 16)?,
+            },
+            1 => EditOp::Remove { range },
+            _ => EditOp::Replace {
+                range,
+                text: fragments(u, 16)?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EditOp, RipString};
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary() {
+        let data = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let rope = RipString::arbitrary(&mut Unstructured::new(&data[..1024])).unwrap();
+        assert!(!rope.is_empty());
+
+        let mut u = Unstructured::new(&data[1024..]);
+
+        while !u.is_empty() {
+            let op = EditOp::arbitrary(&mut u).unwrap();
+            let (range, _) = op.resolve(rope.len());
+            assert!(range.start <= range.end && range.end <= rope.len());
+        }
+
+        let op = EditOp::Replace {
+            range: 12..20,
+            text: "x".to_string(),
+        };
+        assert_eq!(op.resolve(5), (0..5, "x"));
+        assert_eq!(op.resolve(14), (12..14, "x"));
+    }
+}
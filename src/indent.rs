@@ -0,0 +1,184 @@
+use crate::RipString;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Number of lines inspected by `detect_indentation`.
+const INDENT_SAMPLE_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Tabs,
+    /// Indentation by the given number of spaces.
+    Spaces(usize),
+}
+
+impl Indent {
+    fn text(self) -> String {
+        match self {
+            Indent::Tabs => String::from("\t"),
+            Indent::Spaces(width) => " ".repeat(width),
+        }
+    }
+}
+
+impl RipString {
+    /// Prepends one indent unit to every non-empty line of the range.
+    pub fn indent(&mut self, lines: Range<usize>, unit: Indent) {
+        let text = unit.text();
+        let mut edits = Vec::new();
+        let mut next_start = self.line_to_index(lines.start);
+        for _ in lines {
+            let start = match next_start {
+                Some(start) => start,
+                None => break,
+            };
+            let (end, next) = self.line_end(start);
+            next_start = next;
+
+            if start < end {
+                edits.push((start..start, text.as_str()));
+            }
+        }
+
+        if !edits.is_empty() {
+            self.apply_edits(&edits);
+        }
+    }
+
+    /// Removes up to one indent unit from the start of every line of the range. A line
+    /// indented with a tab loses the tab whatever the unit is.
+    pub fn outdent(&mut self, lines: Range<usize>, unit: Indent) {
+        let width = match unit {
+            Indent::Tabs => 0,
+            Indent::Spaces(width) => width,
+        };
+        let mut edits = Vec::new();
+        let mut next_start = self.line_to_index(lines.start);
+        for _ in lines {
+            let start = match next_start {
+                Some(start) => start,
+                None => break,
+            };
+            let (end, next) = self.line_end(start);
+            next_start = next;
+
+            let mut removed = 0;
+            let mut done = false;
+            self.for_each_unit(start..end, |unit| {
+                if done {
+                    return;
+                }
+                if unit == "\t" && removed == 0 {
+                    removed = 1;
+                    done = true;
+                } else if unit == " " && removed < width {
+                    removed += 1;
+                } else {
+                    done = true;
+                }
+            });
+            if removed > 0 {
+                edits.push((start..start + removed, ""));
+            }
+        }
+
+        if !edits.is_empty() {
+            self.apply_edits(&edits);
+        }
+    }
+
+    /// Detects the indentation style from the leading whitespace of the first lines.
+    ///
+    /// The width of space indentation is the most common increase of indentation between
+    /// consecutive non-blank lines. Defaults to four spaces if nothing is indented.
+    pub fn detect_indentation(&self) -> Indent {
+        let mut tabs = 0;
+        let mut spaces = 0;
+        let mut widths = BTreeMap::new();
+        let mut prev_width = 0;
+
+        let mut next_line = Some(0);
+        for _ in 0..INDENT_SAMPLE_LINES {
+            let start = match next_line {
+                Some(start) => start,
+                None => break,
+            };
+            let (end, next) = self.line_end(start);
+            next_line = next;
+
+            let indent_end = self.scan_while(start, |unit| unit == " " || unit == "\t");
+            if indent_end >= end {
+                continue;
+            }
+            let mut width = 0;
+            let mut leading_tab = false;
+            self.for_each_unit(start..indent_end, |unit| {
+                leading_tab |= width == 0 && unit == "\t";
+                width += 1;
+            });
+
+            if leading_tab {
+                tabs += 1;
+            } else {
+                if width > 0 {
+                    spaces += 1;
+                }
+                if width > prev_width {
+                    *widths.entry(width - prev_width).or_insert(0) += 1;
+                }
+                prev_width = width;
+            }
+        }
+
+        if tabs > spaces {
+            return Indent::Tabs;
+        }
+        widths
+            .into_iter()
+            .max_by_key(|(width, count)| (*count, usize::MAX - width))
+            .map_or(Indent::Spaces(4), |(width, _)| Indent::Spaces(width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Indent, RipString};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_detect_indentation() {
+        let a = RipString::from("fn main() {\n  if x {\n    y();\n\n  }\n  z();\n}\n");
+        assert_eq!(a.detect_indentation(), Indent::Spaces(2));
+
+        let a = RipString::from("a:\r\n\tb:\r\n\t\tc\r\n   \td\r\n");
+        assert_eq!(a.detect_indentation(), Indent::Tabs);
+
+        let a = RipString::from(
+            "/**\n * doc\n */\nfn a() {\n    b();\n    if c {\n        d();\n    }\n}",
+        );
+        assert_eq!(a.detect_indentation(), Indent::Spaces(4));
+
+        assert_eq!(
+            RipString::from("a\nb").detect_indentation(),
+            Indent::Spaces(4)
+        );
+    }
+
+    #[test]
+    fn test_indent() {
+        let mut a = RipString::from("a\r\n\n  b\n\tc\nd");
+        a.indent(0..4, Indent::Spaces(2));
+        assert_eq!(a.to_string(), "  a\r\n\n    b\n  \tc\nd");
+        a.indent(3..10, Indent::Tabs);
+        assert_eq!(a.to_string(), "  a\r\n\n    b\n\t  \tc\n\td");
+
+        a.outdent(0..5, Indent::Spaces(4));
+        assert_eq!(a.to_string(), "a\r\n\nb\n  \tc\nd");
+        a.outdent(0..5, Indent::Spaces(1));
+        assert_eq!(a.to_string(), "a\r\n\nb\n \tc\nd");
+        a.outdent(3..4, Indent::Tabs);
+        assert_eq!(a.to_string(), "a\r\n\nb\n \tc\nd");
+    }
+}
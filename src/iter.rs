@@ -0,0 +1,212 @@
+use crate::segment::{self, Segment};
+use crate::RipString;
+use alloc::string::String;
+use core::cmp::min;
+use core::ops::{Range, RangeBounds};
+use core::slice;
+
+pub struct Chars<'a> {
+    nodes: slice::Iter<'a, Segment>,
+    current: segment::Chars<'a>,
+    range: Range<usize>,
+}
+
+impl<'a> Chars<'a> {
+    pub(crate) fn new(nodes: &'a [Segment]) -> Chars<'a> {
+        Chars::in_range(nodes, 0..usize::MAX)
+    }
+
+    /// Iterates over the chars of the units in the range. `nodes` must start with the
+    /// segment containing the start of the range.
+    pub(crate) fn in_range(nodes: &'a [Segment], range: Range<usize>) -> Chars<'a> {
+        Chars {
+            nodes: nodes.iter(),
+            current: segment::Chars::Ascii([].iter()),
+            range,
+        }
+    }
+}
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ch) = self.current.next() {
+                return Some(ch);
+            }
+            let node = self.nodes.next()?;
+            if node.index() >= self.range.end {
+                return None;
+            }
+            let start = self.range.start.saturating_sub(node.index());
+            let end = min(self.range.end - node.index(), node.len());
+            if start < end {
+                self.current = node.tp().chars(start..end);
+            }
+        }
+    }
+}
+
+/// Iterator over the units of a range, which is removed from the string when the
+/// iterator is dropped. A `\r\n` pair is yielded as one item. Returned by
+/// `RipString::drain`.
+#[derive(Debug)]
+pub struct Drain<'a> {
+    rip: &'a mut RipString,
+    range: Range<usize>,
+    front: usize,
+    back: usize,
+    /// Segments containing the units at `front` and `back - 1`.
+    front_seg: usize,
+    back_seg: usize,
+}
+
+impl RipString {
+    /// Removes the range and yields its units, with a `\r\n` pair as one item. The range
+    /// is removed when the iterator is dropped, even if it was not consumed. Panics if
+    /// the range is inverted or out of bounds.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        let front_seg = self.find_segment(range.start);
+        let back_seg = self.find_segment(range.end.saturating_sub(1));
+        Drain {
+            front: range.start,
+            back: range.end,
+            range,
+            rip: self,
+            front_seg,
+            back_seg,
+        }
+    }
+}
+
+impl Drain<'_> {
+    /// Returns the segment holding the unit at `index`, moving `seg` to it.
+    fn node(&self, seg: &mut usize, index: usize) -> &Segment {
+        let nodes = &self.rip.nodes;
+        while index >= nodes[*seg].index() + nodes[*seg].len() {
+            *seg += 1;
+        }
+        while index < nodes[*seg].index() {
+            *seg -= 1;
+        }
+        &nodes[*seg]
+    }
+
+    fn unit_is(&self, seg: &mut usize, index: usize, unit: &str) -> bool {
+        let node = self.node(seg, index);
+        let index = index - node.index();
+        let mut found = false;
+        node.for_each_unit(index..index + 1, |u| found = u == unit);
+        found
+    }
+
+    fn unit(&self, seg: &mut usize, index: usize) -> String {
+        let node = self.node(seg, index);
+        node.tp().unit(index - node.index()).into_owned()
+    }
+}
+
+impl Iterator for Drain<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.front == self.back {
+            return None;
+        }
+        let mut seg = self.front_seg;
+        let mut unit = self.unit(&mut seg, self.front);
+        self.front += 1;
+        if unit == "\r" && self.front < self.back && self.unit_is(&mut seg, self.front, "\n") {
+            unit.push('\n');
+            self.front += 1;
+        }
+        self.front_seg = seg;
+        Some(unit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len.div_ceil(2), Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    fn next_back(&mut self) -> Option<String> {
+        if self.front == self.back {
+            return None;
+        }
+        let mut seg = self.back_seg;
+        self.back -= 1;
+        let mut unit = self.unit(&mut seg, self.back);
+        if unit == "\n" && self.back > self.front && self.unit_is(&mut seg, self.back - 1, "\r") {
+            unit.insert(0, '\r');
+            self.back -= 1;
+        }
+        self.back_seg = seg;
+        Some(unit)
+    }
+}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        self.rip.remove(self.range.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_drain() {
+        let mut a = RipString::from("Hello мир e\u{301}🏡!");
+        let removed = a.drain(6..12).collect::<Vec<_>>();
+        assert_eq!(removed, ["м", "и", "р", " ", "e\u{301}", "🏡"]);
+        assert_eq!(a.to_string(), "Hello !");
+
+        let mut drain = a.drain(..5);
+        assert_eq!(drain.size_hint(), (3, Some(5)));
+        assert_eq!(drain.next_back().as_deref(), Some("o"));
+        drop(drain);
+        assert_eq!(a.to_string(), " !");
+
+        let text = "line мир 🏡\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        let removed = a.drain(100..3000).collect::<String>();
+        let (start, end) = (
+            a.index_to_byte(100),
+            text.char_indices().nth(3000).unwrap().0,
+        );
+        assert_eq!(removed, text[start..end]);
+        assert_eq!(a.to_string(), [&text[..start], &text[end..]].concat());
+    }
+
+    #[test]
+    fn test_drain_crlf() {
+        let mut a = RipString::from("a\r\nb\r\n");
+        let removed = a.drain(..).collect::<Vec<_>>();
+        assert_eq!(removed, ["a", "\r\n", "b", "\r\n"]);
+        assert!(a.is_empty());
+
+        let mut a = RipString::from("a\r\nb\r\n");
+        let mut drain = a.drain(1..5);
+        assert_eq!(drain.next_back().as_deref(), Some("\r"));
+        assert_eq!(drain.next_back().as_deref(), Some("b"));
+        assert_eq!(drain.next().as_deref(), Some("\r\n"));
+        assert_eq!(drain.next(), None);
+        drop(drain);
+        assert_eq!(a.to_string(), "a\n");
+
+        let text = "line\r\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        assert!(a.nodes.len() > 1);
+        let removed = a.drain(..).rev().collect::<Vec<_>>();
+        assert_eq!(removed.len(), 1500);
+        assert_eq!(removed.concat(), "\r\nenil".repeat(300));
+    }
+}
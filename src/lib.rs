@@ -1,22 +1,135 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-use crate::segment::Segment;
-use crate::splitter::Splitter;
-use alloc::fmt::{Display, Formatter};
+use crate::batch::push_segment;
+use crate::segment::{Segment, SegmentType};
+use crate::segmenter::Graphemes;
+use crate::splitter::{split_ascii, Splitter, SplitterConfig, MAX_BLOCK_SIZE};
+use alloc::borrow::Cow;
+use alloc::fmt::{self, Display, Formatter, Write as _};
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::min;
 use core::mem;
-use core::ops::Range;
+use core::ops::{Bound, Range, RangeBounds};
+use core::str;
 
+#[cfg(feature = "bidi")]
+pub use crate::bidi::{BidiLine, BidiRun};
+pub use crate::buffer::TextBuffer;
+pub use crate::builder::RopeBuilder;
+#[cfg(any(feature = "fuzz", feature = "quickcheck", feature = "test-util", test))]
+pub use crate::edit_op::EditOp;
+pub use crate::error::{InvariantError, RipError};
+pub use crate::escape::Escaped;
+pub use crate::indent::Indent;
+pub use crate::iter::{Chars, Drain};
+pub use crate::line_ops::SortOptions;
+#[cfg(feature = "line-break")]
+pub use crate::linebreak::LineBreak;
+pub use crate::lines::LineEnding;
+#[cfg(feature = "normalization")]
+pub use crate::normalize::NormalizationForm;
+pub use crate::pattern::Pattern;
+#[cfg(feature = "legacy-graphemes")]
+pub use crate::segmenter::Legacy;
+#[cfg(feature = "seshat")]
+pub use crate::segmenter::Seshat;
+pub use crate::segmenter::{Extended, Segmenter};
+pub use crate::slice::RipSlice;
+pub use crate::stats::TextStats;
+pub use crate::transaction::Transaction;
+pub use crate::utf16::EncodeUtf16;
+pub use crate::whitespace::WhitespaceOptions;
+#[cfg(feature = "word-dictionary")]
+pub use crate::words::WordDictionary;
+pub use crate::words::Words;
+pub use crate::wrap::WrappedLines;
+
+mod batch;
+#[cfg(any(test, feature = "bench-util"))]
+pub mod bench_util;
+#[cfg(feature = "bidi")]
+mod bidi;
+mod buffer;
+mod builder;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod case;
+mod cmp;
+mod column;
+pub mod compat;
+mod dump;
+#[cfg(any(feature = "fuzz", feature = "quickcheck", feature = "test-util", test))]
+mod edit_op;
+mod emoji;
+mod error;
+mod escape;
+#[cfg(feature = "uniffi")]
+pub mod ffi;
+#[cfg(feature = "fuzz")]
+mod fuzz;
+mod indent;
+mod iter;
+mod line_ops;
+#[cfg(feature = "line-break")]
+mod linebreak;
+mod lines;
+#[cfg(feature = "node")]
+pub mod node;
+#[cfg(feature = "normalization")]
+mod normalize;
+mod offsets;
+mod pattern;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(any(test, feature = "bench-util", feature = "test-util"))]
+mod rng;
+mod scan;
 pub(crate) mod segment;
-pub(crate) mod splitter;
+pub(crate) mod segmenter;
+#[cfg(feature = "serde")]
+mod serde;
+mod slice;
+mod snapshot;
+#[cfg(any(test, feature = "test-util"))]
+pub mod soak;
+mod split;
+pub mod splitter;
+mod stats;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+mod transaction;
+mod utf16;
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod whitespace;
+#[cfg(feature = "width")]
+mod width;
+mod words;
+mod wrap;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+/// Number of neighbouring indices inspected when looking for a grapheme boundary.
+const GRAPHEME_CONTEXT: usize = 4;
 
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct RipString {
     nodes: Vec<Segment>,
     /// Index of last edit node.
     last_edit: usize,
+    segmenter: &'static dyn Segmenter,
+    /// NFC-normalize inserted text.
+    #[cfg(feature = "normalization")]
+    normalize_on_insert: bool,
+    #[cfg(feature = "word-dictionary")]
+    word_dictionary: Option<&'static dyn WordDictionary>,
 }
 
 impl RipString {
@@ -25,26 +138,311 @@ impl RipString {
         RipString {
             nodes: vec![seq],
             last_edit: 0,
+            segmenter: segmenter::DEFAULT,
+            #[cfg(feature = "normalization")]
+            normalize_on_insert: false,
+            #[cfg(feature = "word-dictionary")]
+            word_dictionary: None,
+        }
+    }
+
+    /// Creates a string which breaks grapheme clusters with the given segmenter.
+    pub fn with_segmenter(val: &str, segmenter: &'static dyn Segmenter) -> RipString {
+        RipString::with_splitter_config(val, segmenter, SplitterConfig::default())
+    }
+
+    /// Creates a string whose initial segments are cut with the given splitter settings.
+    pub fn with_splitter_config(
+        val: &str,
+        segmenter: &'static dyn Segmenter,
+        config: SplitterConfig,
+    ) -> RipString {
+        RipString::from_segment_types(Splitter::with_config(val, segmenter, config), segmenter)
+    }
+
+    /// Creates a string from segment payloads in text order.
+    fn from_segment_types<I: IntoIterator<Item = SegmentType>>(
+        segments: I,
+        segmenter: &'static dyn Segmenter,
+    ) -> RipString {
+        let (_, _, mut nodes) = segments.into_iter().fold(
+            (0, 0, vec![]),
+            |(mut index, mut byte_index, mut acc), seg| {
+                let mut seg = Segment::new(index, seg);
+                seg.set_byte_index(byte_index);
+                index += seg.len();
+                byte_index += seg.byte_len();
+                acc.push(seg);
+                (index, byte_index, acc)
+            },
+        );
+
+        if nodes.is_empty() {
+            nodes.push(Segment::default());
+        }
+
+        RipString {
+            nodes,
+            last_edit: 0,
+            segmenter,
+            #[cfg(feature = "normalization")]
+            normalize_on_insert: false,
+            #[cfg(feature = "word-dictionary")]
+            word_dictionary: None,
         }
     }
 
-    pub fn edit(&mut self, range: Range<usize>, new: &str) {
+    pub fn segmenter(&self) -> &'static dyn Segmenter {
+        self.segmenter
+    }
+
+    /// Replaces the range with `new`. Panics if the range is inverted or out of bounds.
+    pub fn edit<R: RangeBounds<usize>>(&mut self, range: R, new: &str) {
+        if let Err(err) = self.try_edit(range, new) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Like `edit`, but returns an error instead of panicking on a bad range.
+    pub fn try_edit<R: RangeBounds<usize>>(&mut self, range: R, new: &str) -> Result<(), RipError> {
+        let range = self.resolve_range(range);
+        self.check_range(&range)?;
+        let new = self.inserted_text(new);
+        let new = new.as_ref();
         if range.is_empty() {
-            if new.is_empty() {
-                return;
+            if !new.is_empty() {
+                self.insert_text(range.start, new);
             }
-            self.insert(range.start, new);
         } else if new.is_empty() {
             self.cut(range);
         } else {
             self.replace(range, new);
         }
+        Ok(())
+    }
+
+    /// Like `edit`, but rejects ranges whose bounds fall inside a grapheme cluster.
+    pub fn edit_checked(&mut self, range: Range<usize>, new: &str) -> Result<(), RipError> {
+        self.check_range(&range)?;
+        for index in [range.start, range.end] {
+            if !self.is_grapheme_boundary(index) {
+                return Err(RipError::SplitsGrapheme(index));
+            }
+        }
+        self.edit(range, new);
+        Ok(())
+    }
+
+    /// Panics with the error of `check_range`.
+    fn check_or_panic(&self, range: &Range<usize>) {
+        if let Err(err) = self.check_range(range) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Turns range bounds into a range, with missing bounds at the ends of the text.
+    fn resolve_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.saturating_add(1),
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.len(),
+        };
+        start..end
+    }
+
+    fn check_range(&self, range: &Range<usize>) -> Result<(), RipError> {
+        if range.start > range.end {
+            return Err(RipError::InvertedRange {
+                start: range.start,
+                end: range.end,
+            });
+        }
+        let len = self.len();
+        if range.end > len {
+            return Err(RipError::OutOfBounds {
+                index: range.end,
+                len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns true if `index` does not fall inside a grapheme cluster.
+    ///
+    /// Clusters may span several indices when an edit or a block split separates
+    /// their code points (e.g. `\r\n` or a ZWJ sequence cut between two segments).
+    pub fn is_grapheme_boundary(&self, index: usize) -> bool {
+        let len = self.len();
+        if index == 0 || index >= len {
+            return true;
+        }
+
+        let start = index.saturating_sub(GRAPHEME_CONTEXT);
+        let (text, offsets) = self.text_with_offsets(start..min(index + GRAPHEME_CONTEXT, len));
+        let split = offsets[index - start];
+
+        let mut offset = 0;
+        for grapheme in Graphemes::new(&text, self.segmenter) {
+            if offset >= split {
+                break;
+            }
+            offset += grapheme.len();
+        }
+        offset == split
+    }
+
+    /// Moves `index` back to the start of the grapheme cluster containing it.
+    pub fn snap_to_grapheme(&self, index: usize) -> usize {
+        let mut index = index;
+        while !self.is_grapheme_boundary(index) {
+            index -= 1;
+        }
+        index
+    }
+
+    pub fn chars(&self) -> Chars<'_> {
+        Chars::new(&self.nodes)
+    }
+
+    /// Inserts `text` at `at`. Panics if `at` is past the end.
+    pub fn insert(&mut self, at: usize, text: &str) {
+        self.check_or_panic(&(at..at));
+        if !text.is_empty() {
+            let text = self.inserted_text(text);
+            self.insert_text(at, text.as_ref());
+        }
+    }
+
+    /// Inserts `ch` at `at`. An ASCII char going into an ASCII segment and a one- or
+    /// two-byte char going into a `Utf8` segment are stored in place without running the
+    /// splitter; wider chars go through `insert`, which places them in a `Unicode` segment.
+    /// Panics if `at` is past the end.
+    pub fn insert_char(&mut self, at: usize, ch: char) {
+        self.check_or_panic(&(at..at));
+        #[cfg(feature = "normalization")]
+        let normalize = self.normalize_on_insert;
+        #[cfg(not(feature = "normalization"))]
+        let normalize = false;
+
+        let seg_index = self.find_segment(at);
+        let node = &mut self.nodes[seg_index];
+        let pos = at - node.index();
+        let inserted = !normalize
+            && node.len() < MAX_BLOCK_SIZE
+            && node.modify(|tp| match tp {
+                SegmentType::Ascii(val) if ch.is_ascii() => {
+                    val.insert(pos, ch as u8);
+                    true
+                }
+                SegmentType::Utf8(val) if !ch.is_ascii() && ch.len_utf8() <= 2 => {
+                    val.insert(pos, ch);
+                    true
+                }
+                _ => false,
+            });
+        if inserted {
+            self.finish_edit(seg_index);
+        } else {
+            self.insert(at, ch.encode_utf8(&mut [0; 4]));
+        }
+    }
+
+    /// Like `edit`, but takes ownership of the text. ASCII text skips the splitter and
+    /// its first block keeps the allocation of `new`; other text goes through `edit`.
+    pub fn edit_owned<R: RangeBounds<usize>>(&mut self, range: R, new: String) {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        if !new.is_ascii() {
+            self.edit(range, &new);
+            return;
+        }
+        self.remove(range.clone());
+        if new.is_empty() {
+            return;
+        }
+
+        let seg_index = self.find_segment(range.start);
+        let index = self.nodes[seg_index].index();
+        let mut head = mem::take(&mut self.nodes[seg_index]).into_tp();
+        let tail = head.split(range.start - index);
+        let mut nodes = Vec::new();
+        push_segment(&mut nodes, head);
+        for tp in split_ascii(new, self.segmenter) {
+            push_segment(&mut nodes, tp);
+        }
+        push_segment(&mut nodes, tail);
+        nodes[0].set_index(index);
+        self.nodes.splice(seg_index..seg_index + 1, nodes);
+        self.finish_edit(seg_index);
+    }
+
+    /// Like `insert`, but takes ownership of the text, see `edit_owned`.
+    pub fn insert_owned(&mut self, at: usize, text: String) {
+        self.edit_owned(at..at, text);
+    }
+
+    /// Removes the range. Panics if the range is inverted or out of bounds.
+    pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        if !range.is_empty() {
+            self.cut(range);
+        }
+    }
+
+    /// Removes the last grapheme cluster and returns it, or `None` if the string is empty.
+    pub fn pop(&mut self) -> Option<String> {
+        match self.len() {
+            0 => None,
+            len => Some(self.remove_grapheme(len - 1)),
+        }
+    }
+
+    /// Removes the grapheme cluster at `index` and returns it. A `\r\n` is removed as a
+    /// whole from either of its units. Panics if `index` is out of bounds.
+    pub fn remove_grapheme(&mut self, index: usize) -> String {
+        let len = self.len();
+        if index >= len {
+            panic!("{}", RipError::OutOfBounds { index, len });
+        }
+        let mut range = index..index + 1;
+        if self.unit_is(index, "\r") && index + 1 < len && self.unit_is(index + 1, "\n") {
+            range.end += 1;
+        } else if index > 0 && self.unit_is(index, "\n") && self.unit_is(index - 1, "\r") {
+            range.start -= 1;
+        }
+        let text = self.collect_range(range.clone());
+        self.cut(range);
+        text
+    }
+
+    /// Appends `text` to the last segment, or after it when it is full.
+    pub fn push_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let text = self.inserted_text(text);
+        let seg_index = self.nodes.len() - 1;
+        let node = &mut self.nodes[seg_index];
+        if let Some(new_nodes) = node.insert(node.index() + node.len(), &text, self.segmenter) {
+            self.nodes.extend(new_nodes);
+        }
+        self.finish_edit(seg_index);
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
     }
 
-    fn insert(&mut self, index: usize, new: &str) {
+    fn insert_text(&mut self, index: usize, new: &str) {
         let seg_index = self.find_segment(index);
         let node = &mut self.nodes[seg_index];
-        if let Some(new_nodes) = node.insert(index, new) {
+        if let Some(new_nodes) = node.insert(index, new, self.segmenter) {
             if seg_index == self.nodes.len() - 1 {
                 self.nodes.extend(new_nodes);
             } else {
@@ -53,8 +451,7 @@ impl RipString {
                 self.nodes.extend(suffix);
             }
         }
-        self.last_edit = seg_index;
-        self.fix_index_from(seg_index);
+        self.finish_edit(seg_index);
     }
 
     fn cut(&mut self, range: Range<usize>) {
@@ -88,16 +485,17 @@ impl RipString {
                     .map(|(_, b)| b),
             );
         }
-        self.last_edit = seg_index;
-        self.fix_index_from(seg_index);
+        self.finish_edit(seg_index);
     }
 
     pub fn replace(&mut self, range: Range<usize>, new: &str) {
+        let new = self.inserted_text(new);
+        let new = new.as_ref();
         let seg_index = self.find_segment(range.start);
         let last_seg_index = self.find_segment(range.end);
 
         let node = &mut self.nodes[seg_index];
-        let new_nodes = node.replace(range.clone(), new);
+        let new_nodes = node.replace(range.clone(), new, self.segmenter);
         if seg_index != last_seg_index {
             let node = &mut self.nodes[last_seg_index];
             if let Some(node) = node.cut(node.index()..range.end) {
@@ -115,16 +513,228 @@ impl RipString {
             }
         }
 
-        self.last_edit = seg_index;
-        self.fix_index_from(seg_index);
+        self.finish_edit(seg_index);
+    }
+
+    /// Returns the text of the range, visiting only the segments it covers. Panics if the
+    /// range is inverted or out of bounds.
+    pub fn substring<R: RangeBounds<usize>>(&self, range: R) -> String {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        let mut text = String::new();
+        self.for_each_chunk(range, |chunk| text.push_str(chunk));
+        text
+    }
+
+    /// Returns the text of the range, borrowed when the range lies inside a single ASCII
+    /// segment. Panics if the range is inverted or out of bounds.
+    pub fn to_cow(&self, range: Range<usize>) -> Cow<'_, str> {
+        self.check_or_panic(&range);
+        if range.is_empty() {
+            return Cow::Borrowed("");
+        }
+        let mut seg_index = self.find_segment(range.start);
+        // A segment also matches the index right after its end.
+        if range.start >= self.nodes[seg_index].index() + self.nodes[seg_index].len() {
+            seg_index += 1;
+        }
+        let node = &self.nodes[seg_index];
+        if let SegmentType::Ascii(val) = node.tp() {
+            let end = range.end - node.index();
+            if end <= val.len() {
+                if let Ok(text) = str::from_utf8(&val[range.start - node.index()..end]) {
+                    return Cow::Borrowed(text);
+                }
+            }
+        }
+        Cow::Owned(self.collect_range(range))
+    }
+
+    /// Returns the whole text if it is stored in a single ASCII segment.
+    pub fn as_contiguous(&self) -> Option<&str> {
+        self.as_bytes_contiguous()
+            .and_then(|bytes| str::from_utf8(bytes).ok())
+    }
+
+    /// Returns the bytes of the whole text if it is stored in a single ASCII segment.
+    pub fn as_bytes_contiguous(&self) -> Option<&[u8]> {
+        match self.nodes.as_slice() {
+            [node] => match node.tp() {
+                SegmentType::Ascii(val) => Some(val),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Converts the string into a `String` sized by the byte length. Whole segment
+    /// buffers are copied, and the buffer of a single ASCII segment is reused.
+    pub fn into_string(mut self) -> String {
+        if let [node] = self.nodes.as_mut_slice() {
+            let ascii = node.modify(|tp| match tp {
+                SegmentType::Ascii(val) => Some(mem::take(val)),
+                _ => None,
+            });
+            if let Some(val) = ascii {
+                return String::from_utf8(val).unwrap_or_default();
+            }
+        }
+
+        let mut text = String::with_capacity(self.len_bytes());
+        for node in &self.nodes {
+            match node.tp() {
+                SegmentType::Ascii(val) => text.push_str(str::from_utf8(val).unwrap_or_default()),
+                SegmentType::Utf8(val) => text.extend(val),
+                SegmentType::Unicode(val) => {
+                    val.iter().for_each(|grapheme| text.push_str(grapheme))
+                }
+            }
+        }
+        text
+    }
+
+    /// Returns the text to insert, NFC-normalized when normalize-on-insert is enabled.
+    fn inserted_text<'a>(&self, new: &'a str) -> Cow<'a, str> {
+        #[cfg(feature = "normalization")]
+        if self.normalize_on_insert && !NormalizationForm::Nfc.is_normalized(new.chars()) {
+            return Cow::Owned(NormalizationForm::Nfc.normalize(new.chars()));
+        }
+        Cow::Borrowed(new)
     }
 
     fn fix_index_from(&mut self, seg_index: usize) {
+        let mut next_byte = match seg_index.checked_sub(1) {
+            Some(prev) => self.nodes[prev].byte_index() + self.nodes[prev].byte_len(),
+            None => 0,
+        };
         let last_right_node = &self.nodes[seg_index];
         let mut next_index = last_right_node.index() + last_right_node.len();
-        for i in seg_index + 1..self.nodes.len() {
-            self.nodes[i].set_index(next_index);
-            next_index += self.nodes[i].len();
+        for i in seg_index..self.nodes.len() {
+            if i > seg_index {
+                self.nodes[i].set_index(next_index);
+                next_index += self.nodes[i].len();
+            }
+            self.nodes[i].set_byte_index(next_byte);
+            next_byte += self.nodes[i].byte_len();
+        }
+    }
+
+    /// Replaces all segments and recomputes their indices.
+    fn reset_nodes(&mut self, mut nodes: Vec<Segment>) {
+        if nodes.is_empty() {
+            nodes.push(Segment::default());
+        }
+        nodes[0].set_index(0);
+        self.nodes = nodes;
+        self.finish_edit(0);
+    }
+
+    /// Records the edited segment and reindexes the following ones. With the
+    /// `strict-invariants` feature, debug builds validate the structure afterwards.
+    fn finish_edit(&mut self, seg_index: usize) {
+        self.fix_index_from(seg_index);
+        self.last_edit = self.remove_empty_segments(seg_index);
+        #[cfg(all(feature = "strict-invariants", debug_assertions))]
+        if let Err(err) = self.validate() {
+            panic!("Broken invariant after edit: {}", err);
+        }
+    }
+
+    /// Drops segments emptied by an edit, keeping at least one. Edits only empty the
+    /// segments from `seg_index` on, so earlier ones are not visited. Returns the
+    /// position of the edited segment among the remaining ones.
+    fn remove_empty_segments(&mut self, seg_index: usize) -> usize {
+        if self.nodes.len() < 2 || self.nodes[seg_index..].iter().all(|node| node.len() != 0) {
+            return seg_index;
+        }
+        let tail = self.nodes.split_off(seg_index);
+        self.nodes
+            .extend(tail.into_iter().filter(|node| node.len() != 0));
+        if self.nodes.is_empty() {
+            self.nodes.push(Segment::default());
+        }
+        min(seg_index, self.nodes.len() - 1)
+    }
+
+    /// Returns the length in units, the indices taken by all other methods.
+    pub fn len(&self) -> usize {
+        let last = &self.nodes[self.nodes.len() - 1];
+        last.index() + last.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the length of the text in UTF-8 bytes.
+    pub fn len_bytes(&self) -> usize {
+        let last = &self.nodes[self.nodes.len() - 1];
+        last.byte_index() + last.byte_len()
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.nodes.iter().map(|node| node.tp().char_count()).sum()
+    }
+
+    /// Returns the number of grapheme clusters. Units are usually whole clusters, but a
+    /// `\r\n` or an edit such as a combining mark inserted after its base leaves a
+    /// cluster split over several units, so the units are run through the segmenter.
+    pub fn len_graphemes(&self) -> usize {
+        let mut count = 0;
+        let mut pending = String::new();
+        self.for_each_unit(0..self.len(), |unit| {
+            pending.push_str(unit);
+            let mut last = 0;
+            for cluster in Graphemes::new(&pending, self.segmenter) {
+                count += 1;
+                last = cluster.len();
+            }
+            // The last cluster may continue in the next unit.
+            count -= 1;
+            pending.drain(..pending.len() - last);
+        });
+        count + !pending.is_empty() as usize
+    }
+
+    fn collect_range(&self, range: Range<usize>) -> String {
+        let mut text = String::new();
+        self.for_each_unit(range, |unit| text.push_str(unit));
+        text
+    }
+
+    /// Returns the text of the range with the byte offset of every unit in it, followed
+    /// by the length of the text.
+    fn text_with_offsets(&self, range: Range<usize>) -> (String, Vec<usize>) {
+        let mut text = String::new();
+        let mut offsets = Vec::with_capacity(range.len() + 1);
+        self.for_each_unit(range, |unit| {
+            offsets.push(text.len());
+            text.push_str(unit);
+        });
+        offsets.push(text.len());
+        (text, offsets)
+    }
+
+    fn for_each_unit<F: FnMut(&str)>(&self, range: Range<usize>, mut f: F) {
+        self.for_each_node_range(range, |node, range| node.for_each_unit(range, &mut f));
+    }
+
+    fn for_each_chunk<F: FnMut(&str)>(&self, range: Range<usize>, mut f: F) {
+        self.for_each_node_range(range, |node, range| node.for_each_chunk(range, &mut f));
+    }
+
+    /// Calls `f` with every segment intersecting the range and the intersection in
+    /// segment coordinates.
+    fn for_each_node_range<F: FnMut(&Segment, Range<usize>)>(&self, range: Range<usize>, mut f: F) {
+        let mut seg_index = self.find_segment(range.start);
+        while seg_index < self.nodes.len() && self.nodes[seg_index].index() < range.end {
+            let node = &self.nodes[seg_index];
+            let start = range.start.saturating_sub(node.index());
+            let end = min(range.end - node.index(), node.len());
+            if start < end {
+                f(node, start..end);
+            }
+            seg_index += 1;
         }
     }
 
@@ -141,20 +751,30 @@ impl RipString {
 
 impl From<&str> for RipString {
     fn from(val: &str) -> Self {
-        let (_, mut nodes) = Splitter::new(val).fold((0, vec![]), |(mut index, mut acc), seg| {
-            let seg = Segment::new(index, seg);
-            index += seg.len();
-            acc.push(seg);
-            (index, acc)
-        });
+        Self::with_segmenter(val, segmenter::DEFAULT)
+    }
+}
 
-        if nodes.is_empty() {
-            nodes.push(Segment::default());
-        }
+impl From<RipString> for String {
+    fn from(val: RipString) -> Self {
+        val.into_string()
+    }
+}
 
-        RipString {
-            nodes,
-            last_edit: 0,
+/// ASCII text keeps its allocation for the first block, see `edit_owned`.
+impl From<String> for RipString {
+    fn from(val: String) -> Self {
+        let mut rip = RipString::new();
+        rip.insert_owned(0, val);
+        rip
+    }
+}
+
+impl From<Cow<'_, str>> for RipString {
+    fn from(val: Cow<'_, str>) -> Self {
+        match val {
+            Cow::Borrowed(val) => RipString::from(val),
+            Cow::Owned(val) => RipString::from(val),
         }
     }
 }
@@ -165,10 +785,55 @@ impl Default for RipString {
     }
 }
 
+/// Appends formatted text, so `write!` can target a rope.
+impl fmt::Write for RipString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+/// Width and precision are counted in units, unlike `str`, which counts chars: a grapheme
+/// cluster made of several chars is one unit and `\r\n` is two. The precision never
+/// splits a cluster or a `\r\n` pair.
 impl Display for RipString {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        for node in &self.nodes {
-            node.fmt(f)?;
+        if f.width().is_none() && f.precision().is_none() {
+            for node in &self.nodes {
+                node.fmt(f)?;
+            }
+            return Ok(());
+        }
+
+        let mut end = min(f.precision().unwrap_or(usize::MAX), self.len());
+        if end > 0 && end < self.len() && self.unit_is(end - 1, "\r") && self.unit_is(end, "\n") {
+            end -= 1;
+        }
+        let padding = f.width().unwrap_or(0).saturating_sub(end);
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Right) => (padding, 0),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            _ => (0, padding),
+        };
+
+        let fill = f.fill();
+        for _ in 0..before {
+            f.write_char(fill)?;
+        }
+        let mut result = Ok(());
+        self.for_each_chunk(0..end, |chunk| {
+            if result.is_ok() {
+                result = f.write_str(chunk);
+            }
+        });
+        result?;
+        for _ in 0..after {
+            f.write_char(fill)?;
         }
         Ok(())
     }
@@ -176,8 +841,11 @@ impl Display for RipString {
 
 #[cfg(test)]
 mod tests {
-    use crate::RipString;
-    use alloc::string::ToString;
+    use crate::segment::SegmentType;
+    use crate::{RipError, RipString, Segmenter};
+    use alloc::borrow::Cow;
+    use alloc::format;
+    use alloc::string::{String, ToString};
 
     #[test]
     pub fn edit_test() {
@@ -209,4 +877,376 @@ mod tests {
         a.edit(1..9, "era");
         assert_eq!("herald", a.to_string());
     }
+
+    #[test]
+    fn replace_mixed() {
+        let mut a = RipString::from("Hello мир 🏡!");
+        a.edit(6..9, "world");
+        assert_eq!(a.to_string(), "Hello world 🏡!");
+        a.edit(4..13, "o, дом 🏠🏠");
+        assert_eq!(a.to_string(), "Hello, дом 🏠🏠!");
+        a.edit(0..a.len(), "ok");
+        assert_eq!(a.to_string(), "ok");
+
+        let text = "line мир 🏡\n".repeat(200);
+        let mut a = RipString::from(text.as_str());
+        let mut expected = text.clone();
+        a.edit(5..2005, "ß🏠");
+        expected.replace_range(5..expected.char_indices().nth(2005).unwrap().0, "ß🏠");
+        assert_eq!(a.to_string(), expected);
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn try_edit() {
+        let mut a = RipString::from("мир");
+        assert_eq!(
+            a.try_edit(2..4, "!"),
+            Err(RipError::OutOfBounds { index: 4, len: 3 })
+        );
+        let (start, end) = (2, 1);
+        assert_eq!(
+            a.try_edit(start..end, "!"),
+            Err(RipError::InvertedRange { start: 2, end: 1 })
+        );
+        assert_eq!(a.try_edit(3..3, "!"), Ok(()));
+        assert_eq!(a.to_string(), "мир!");
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 5 is out of bounds of length 3")]
+    fn edit_out_of_bounds() {
+        RipString::from("abc").edit(5..5, "!");
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut a = RipString::from("Hello мир");
+        a.insert(5, ",");
+        a.insert(10, " 🏡");
+        a.insert(0, "");
+        assert_eq!(a.to_string(), "Hello, мир 🏡");
+        a.remove(5..6);
+        a.remove(2..2);
+        a.remove(9..11);
+        assert_eq!(a.to_string(), "Hello мир");
+
+        let text = "мир 🏡 ok\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.remove(3..2696);
+        a.insert(3, "!");
+        assert_eq!(a.to_string(), "мир! ok\n");
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn edit_owned() {
+        let text = "0123456789\n".repeat(300);
+        let owned = text.clone();
+        let ptr = owned.as_ptr();
+        let mut a = RipString::new();
+        a.insert_owned(0, owned);
+        match a.nodes[0].tp() {
+            SegmentType::Ascii(val) => assert_eq!(val.as_ptr(), ptr),
+            tp => panic!("Unexpected segment {:?}", tp),
+        }
+        assert_eq!(a.to_string(), text);
+        assert_eq!(a.validate(), Ok(()));
+
+        let mut a = RipString::from("Hello мир 🏡");
+        a.edit_owned(6..9, "world".into());
+        a.insert_owned(5, ",".into());
+        a.edit_owned(12.., "!".into());
+        assert_eq!(a.to_string(), "Hello, world!");
+        a.edit_owned(..5, "Привет".into());
+        a.edit_owned(6..7, String::new());
+        assert_eq!(a.to_string(), "Привет world!");
+        a.insert_owned(7, text.clone());
+        assert_eq!(a.to_string(), format!("Привет {}world!", text));
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn clone() {
+        let text = "ab мир 🏡\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.edit(1500..1501, "!");
+        let mut b = a.clone();
+        assert_eq!(b.last_edit, a.last_edit);
+        assert_eq!(b.dump_structure(), a.dump_structure());
+
+        b.edit(0..2, "");
+        assert_eq!(a.substring(0..3), "ab ");
+        assert_eq!(b.substring(0..3), " ми");
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(b.validate(), Ok(()));
+    }
+
+    #[test]
+    fn from() {
+        let text = "ab мир 🏡\n".repeat(300);
+        let a = RipString::from(text.as_str());
+        assert_eq!(RipString::from(text.clone()), a);
+        assert_eq!(RipString::from(Cow::Borrowed(text.as_str())), a);
+        assert_eq!(RipString::from(Cow::Owned(text.clone())), a);
+        assert_eq!(RipString::from(String::from("ok\r\n")), "ok\r\n");
+        assert!(RipString::from(String::new()).is_empty());
+        assert_eq!(RipString::default(), "");
+        assert_eq!(RipString::from(text).validate(), Ok(()));
+    }
+
+    #[test]
+    fn write() {
+        use core::fmt::Write;
+
+        let mut a = RipString::from("log:");
+        for i in 0..200 {
+            write!(a, " {} мир {:?}", i, '🏡').unwrap();
+            a.write_char('\n').unwrap();
+        }
+        let mut expected = String::from("log:");
+        for i in 0..200 {
+            expected.push_str(&format!(" {} мир '🏡'\n", i));
+        }
+        assert_eq!(a, expected);
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn into_string() {
+        let text = "ab мир 🏡 e\u{301}\r\n".repeat(300);
+        let a = RipString::from(text.as_str());
+        assert!(a.nodes.len() > 1);
+        assert_eq!(a.into_string(), text);
+
+        let owned = String::from("Hello, world");
+        let ptr = owned.as_ptr();
+        let text = String::from(RipString::from(owned));
+        assert_eq!((text.as_str(), text.as_ptr()), ("Hello, world", ptr));
+        assert_eq!(String::from(RipString::from("мир")), "мир");
+        assert_eq!(RipString::new().into_string(), "");
+    }
+
+    #[test]
+    fn display_flags() {
+        let a = RipString::from("мир 🏡 e\u{301}");
+        assert_eq!(format!("{}", a), "мир 🏡 e\u{301}");
+        assert_eq!(format!("[{:8}]", a), "[мир 🏡 e\u{301} ]");
+        assert_eq!(format!("[{:>8}]", a), "[ мир 🏡 e\u{301}]");
+        assert_eq!(format!("[{:*^9}]", a), "[*мир 🏡 e\u{301}*]");
+        assert_eq!(format!("[{:.5}]", a), "[мир 🏡]");
+        assert_eq!(format!("[{:>4.2}]", a), "[  ми]");
+        assert_eq!(format!("[{:3}]", a), "[мир 🏡 e\u{301}]");
+
+        let a = RipString::from("ab\r\ncd");
+        assert_eq!(format!("[{:.3}]", a), "[ab]");
+        assert_eq!(format!("[{:.4}]", a), "[ab\r\n]");
+
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        let a = RipString::from(format!("{}x", family).as_str());
+        assert_eq!(a.len(), 2);
+        assert_eq!(format!("[{:.1}]", a), format!("[{}]", family));
+        assert_eq!(format!("[{:4}]", a), format!("[{}x  ]", family));
+        assert_eq!(format!("{:.1}", format!("{}x", family)), "\u{1f468}");
+    }
+
+    #[test]
+    fn as_contiguous() {
+        let mut a = RipString::from("Hello, world\r\n");
+        assert_eq!(a.as_contiguous(), Some("Hello, world\r\n"));
+        assert_eq!(a.as_bytes_contiguous(), Some(&b"Hello, world\r\n"[..]));
+        a.push_str("мир");
+        assert_eq!(a.as_contiguous(), None);
+        assert_eq!(RipString::new().as_contiguous(), Some(""));
+        assert_eq!(RipString::from("мир").as_bytes_contiguous(), None);
+        assert_eq!(
+            RipString::from("ab\n".repeat(500).as_str()).as_contiguous(),
+            None
+        );
+    }
+
+    #[test]
+    fn push_str() {
+        let mut a = RipString::new();
+        let mut expected = String::new();
+        for i in 0..400 {
+            let piece = ["log line ", "мир ", "🏡", "\r\n"][i % 4];
+            a.push_str(piece);
+            expected.push_str(piece);
+        }
+        a.push('!');
+        a.push_str("");
+        expected.push('!');
+        assert_eq!(a.to_string(), expected);
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn insert_char() {
+        let mut a = RipString::from("Hllo мр");
+        a.insert_char(1, 'e');
+        a.insert_char(7, 'и');
+        a.insert_char(0, '🏡');
+        a.insert_char(10, '!');
+        a.insert_char(1, ' ');
+        assert_eq!(a.to_string(), "🏡 Hello мир!");
+        assert_eq!(a.len(), 12);
+
+        let mut a = RipString::from("a".repeat(1024).as_str());
+        a.insert_char(512, 'b');
+        assert_eq!(a.len(), 1025);
+        assert_eq!(a.validate(), Ok(()));
+
+        let mut a = RipString::from("мир");
+        a.insert_char(1, '世');
+        assert_eq!(a.to_string(), "м世ир");
+        assert_eq!(a.nodes, RipString::from("м世ир").nodes);
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    fn pop_remove_grapheme() {
+        let mut a = RipString::from("aб\r\nc🏡e\u{301}");
+        assert_eq!(a.pop().as_deref(), Some("e\u{301}"));
+        assert_eq!(a.pop().as_deref(), Some("🏡"));
+        assert_eq!(a.remove_grapheme(3), "\r\n");
+        assert_eq!(a.to_string(), "aбc");
+        assert_eq!(a.remove_grapheme(1), "б");
+        assert_eq!(a.pop().as_deref(), Some("c"));
+        assert_eq!(a.pop().as_deref(), Some("a"));
+        assert_eq!(a.pop(), None);
+
+        let mut a = RipString::from("line\r\n".repeat(300).as_str());
+        assert_eq!(a.pop().as_deref(), Some("\r\n"));
+        assert_eq!(a.remove_grapheme(4), "\r\n");
+        assert_eq!(a.len(), 1796);
+        assert_eq!(a.validate(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Range starts at 2 after its end 1")]
+    fn remove_inverted() {
+        let (start, end) = (2, 1);
+        RipString::from("abc").remove(start..end);
+    }
+
+    #[test]
+    fn edit_checked() {
+        let mut a = RipString::from("a\r\nb");
+        assert!(!a.is_grapheme_boundary(2));
+        assert_eq!(a.snap_to_grapheme(2), 1);
+        assert_eq!(a.edit_checked(2..2, "x"), Err(RipError::SplitsGrapheme(2)));
+        assert_eq!(a.edit_checked(0..2, "x"), Err(RipError::SplitsGrapheme(2)));
+        assert_eq!(a.edit_checked(1..3, ""), Ok(()));
+        assert_eq!(a.to_string(), "ab");
+
+        let mut a = RipString::from("e");
+        a.edit(1..1, "\u{301}");
+        assert!(!a.is_grapheme_boundary(1));
+        assert_eq!(a.edit_checked(1..2, "x"), Err(RipError::SplitsGrapheme(1)));
+        assert_eq!(a.edit_checked(0..2, "x"), Ok(()));
+        assert_eq!(a.to_string(), "x");
+    }
+
+    #[test]
+    fn chars() {
+        let a = RipString::from("Hi, мир 🏡!");
+        assert_eq!(a.chars().collect::<String>(), "Hi, мир 🏡!");
+        assert_eq!(RipString::new().chars().count(), 0);
+    }
+
+    #[derive(Debug)]
+    struct CodePoints;
+
+    impl Segmenter for CodePoints {
+        fn grapheme_len(&self, text: &str) -> usize {
+            text.chars().next().map_or(0, char::len_utf8)
+        }
+    }
+
+    #[test]
+    fn custom_segmenter() {
+        let mut a = RipString::with_segmenter("e\u{301}", &CodePoints);
+        assert!(a.is_grapheme_boundary(1));
+        a.edit(1..2, "");
+        assert_eq!(a.to_string(), "e");
+        assert_eq!(a.nodes.len(), 1);
+        assert_eq!(a.validate(), Ok(()));
+
+        let a = RipString::from("e\u{301}");
+        assert_eq!(a.nodes.len(), 1);
+        assert_eq!(a.len(), 1);
+    }
+
+    #[test]
+    fn to_cow() {
+        use alloc::borrow::Cow;
+
+        let a = RipString::from("Hello мир");
+        assert!(matches!(a.to_cow(1..4), Cow::Borrowed("ell")));
+        assert!(matches!(a.to_cow(3..3), Cow::Borrowed("")));
+        assert!(matches!(a.to_cow(4..8), Cow::Owned(_)));
+        assert_eq!(a.to_cow(4..8), "o ми");
+
+        let a = RipString::from("0123456789".repeat(300).as_str());
+        let start = a.nodes[1].index();
+        assert!(matches!(a.to_cow(start..start + 3), Cow::Borrowed(_)));
+        assert_eq!(a.to_cow(start..start + 3), a.substring(start..start + 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 12 is out of bounds")]
+    fn to_cow_out_of_bounds() {
+        RipString::from("Hello мир").to_cow(8..12);
+    }
+
+    #[test]
+    fn substring() {
+        let text = "ok мир e\u{301}🏡\n".repeat(200);
+        let a = RipString::from(text.as_str());
+        let byte = |index| a.index_to_byte(index);
+        for range in [0..0, 1..4, 3..9, 9..10, 500..1700, 0..a.len()] {
+            assert_eq!(
+                a.substring(range.clone()),
+                text[byte(range.start)..byte(range.end)]
+            );
+        }
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut a = RipString::from("Hello мир 🏡");
+        assert_eq!(a.substring(..5), "Hello");
+        assert_eq!(a.substring(6..=8), "мир");
+        assert_eq!(a.substring(10..), "🏡");
+        assert_eq!(a.slice(..).len(), 11);
+        a.edit(9.., "!");
+        a.remove(..=5);
+        assert_eq!(a.to_string(), "мир!");
+        assert_eq!(
+            a.try_edit(..=4, ""),
+            Err(RipError::OutOfBounds { index: 5, len: 4 })
+        );
+        a.edit(.., "");
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn lengths() {
+        let a = RipString::from("e\u{301} мир\r\n🏡");
+        assert_eq!(a.len(), 8);
+        assert_eq!(a.len_bytes(), 16);
+        assert_eq!(a.len_chars(), 9);
+        assert_eq!(a.len_graphemes(), 7);
+        assert!(!a.is_empty());
+
+        let mut b = RipString::from("e");
+        b.insert(1, "\u{301}");
+        b.push_str("\r");
+        b.push_str("\n");
+        assert_eq!(b.len(), 4);
+        assert_eq!(b.len_graphemes(), 2);
+        assert!(RipString::new().is_empty());
+        assert_eq!(RipString::new().len_bytes(), 0);
+    }
 }
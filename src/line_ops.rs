@@ -0,0 +1,215 @@
+use crate::RipString;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::ops::Range;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SortOptions {
+    pub descending: bool,
+    pub ignore_case: bool,
+}
+
+/// Lines of a region with the terminators between them.
+struct Region {
+    range: Range<usize>,
+    lines: Vec<String>,
+    separators: Vec<String>,
+}
+
+fn is_whitespace(unit: &str) -> bool {
+    unit.chars().all(char::is_whitespace)
+}
+
+impl RipString {
+    /// Sorts the given lines. The sort is stable in both directions, line terminators
+    /// keep their positions and the empty line after a trailing terminator stays last.
+    pub fn sort_lines(&mut self, lines: Range<usize>, options: SortOptions) {
+        let mut region = match self.region(lines) {
+            Some(region) => region,
+            None => return,
+        };
+
+        let trailing = match region.lines.last() {
+            Some(line) if line.is_empty() && region.lines.len() > 1 => region.lines.pop(),
+            _ => None,
+        };
+        match (options.ignore_case, options.descending) {
+            (true, false) => region.lines.sort_by_cached_key(|line| line.to_lowercase()),
+            (true, true) => region
+                .lines
+                .sort_by_cached_key(|line| Reverse(line.to_lowercase())),
+            (false, false) => region.lines.sort(),
+            (false, true) => region.lines.sort_by(|a, b| b.cmp(a)),
+        }
+        region.lines.extend(trailing);
+
+        let mut text = String::new();
+        for (i, line) in region.lines.iter().enumerate() {
+            text.push_str(line);
+            if let Some(separator) = region.separators.get(i) {
+                text.push_str(separator);
+            }
+        }
+        self.apply_edits(&[(region.range, text)]);
+    }
+
+    /// Removes lines equal to an earlier line of the range and returns the number of
+    /// removed lines.
+    pub fn dedup_lines(&mut self, lines: Range<usize>) -> usize {
+        let region = match self.region(lines) {
+            Some(region) => region,
+            None => return 0,
+        };
+
+        let mut seen = BTreeSet::new();
+        let mut kept = Vec::with_capacity(region.lines.len());
+        for (i, line) in region.lines.iter().enumerate() {
+            if seen.insert(line.as_str()) {
+                kept.push(i);
+            }
+        }
+        let removed = region.lines.len() - kept.len();
+        if removed == 0 {
+            return 0;
+        }
+
+        let mut text = String::new();
+        for (n, i) in kept.iter().enumerate() {
+            text.push_str(&region.lines[*i]);
+            if n + 1 < kept.len() {
+                text.push_str(&region.separators[*i]);
+            }
+        }
+        self.apply_edits(&[(region.range, text)]);
+        removed
+    }
+
+    fn region(&self, lines: Range<usize>) -> Option<Region> {
+        let start = self.line_to_index(lines.start)?;
+        let mut region = Region {
+            range: start..start,
+            lines: Vec::new(),
+            separators: Vec::new(),
+        };
+
+        let mut line_start = start;
+        for _ in lines {
+            let (end, next) = self.line_end(line_start);
+            region.lines.push(self.collect_range(line_start..end));
+            region.range.end = end;
+            match next {
+                Some(next) => {
+                    region.separators.push(self.collect_range(end..next));
+                    line_start = next;
+                }
+                None => break,
+            }
+        }
+        region.separators.truncate(region.lines.len() - 1);
+        Some(region)
+    }
+
+    /// Removes whitespace at the end of every line and returns the changed lines.
+    pub fn trim_trailing_whitespace(&mut self) -> Vec<usize> {
+        self.trim_trailing_whitespace_in(0..self.line_count())
+    }
+
+    /// Removes whitespace at the end of the given lines as a single edit and returns
+    /// the changed lines.
+    pub fn trim_trailing_whitespace_in(&mut self, lines: Range<usize>) -> Vec<usize> {
+        let mut edits = Vec::new();
+        let mut changed = Vec::new();
+        let mut next_start = self.line_to_index(lines.start);
+        for line in lines {
+            let start = match next_start {
+                Some(start) => start,
+                None => break,
+            };
+            let (end, next) = self.line_end(start);
+            next_start = next;
+
+            let mut index = start;
+            let mut content_end = start;
+            self.for_each_unit(start..end, |unit| {
+                index += 1;
+                if !is_whitespace(unit) {
+                    content_end = index;
+                }
+            });
+            if content_end < end {
+                edits.push((content_end..end, ""));
+                changed.push(line);
+            }
+        }
+
+        if !edits.is_empty() {
+            self.apply_edits(&edits);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipString, SortOptions};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_trim_trailing_whitespace() {
+        let mut a = RipString::from("fn main() {  \r\n    мир\u{a0}\t\n\n   \n}");
+        assert_eq!(a.trim_trailing_whitespace(), [0, 1, 3]);
+        assert_eq!(a.to_string(), "fn main() {\r\n    мир\n\n\n}");
+        assert!(a.trim_trailing_whitespace().is_empty());
+
+        let mut a = RipString::from("a \nb \nc ");
+        assert_eq!(a.trim_trailing_whitespace_in(1..5), [1, 2]);
+        assert_eq!(a.to_string(), "a \nb\nc");
+    }
+
+    #[test]
+    fn test_sort_lines() {
+        let mut a = RipString::from("header\nbanana\r\nЯблоко\napple\nBanana\nfooter");
+        a.sort_lines(1..5, SortOptions::default());
+        assert_eq!(
+            a.to_string(),
+            "header\nBanana\r\napple\nbanana\nЯблоко\nfooter"
+        );
+
+        a.sort_lines(
+            1..5,
+            SortOptions {
+                descending: true,
+                ignore_case: true,
+            },
+        );
+        assert_eq!(
+            a.to_string(),
+            "header\nЯблоко\r\nBanana\nbanana\napple\nfooter"
+        );
+
+        let mut a = RipString::from("b\na\n");
+        a.sort_lines(0..10, SortOptions::default());
+        assert_eq!(a.to_string(), "a\nb\n");
+        a.sort_lines(
+            0..10,
+            SortOptions {
+                descending: true,
+                ..SortOptions::default()
+            },
+        );
+        assert_eq!(a.to_string(), "b\na\n");
+    }
+
+    #[test]
+    fn test_dedup_lines() {
+        let mut a = RipString::from("a\nb\na\r\nc\nb\nd\nb");
+        assert_eq!(a.dedup_lines(0..6), 2);
+        assert_eq!(a.to_string(), "a\nb\nc\nd\nb");
+        assert_eq!(a.dedup_lines(0..5), 1);
+        assert_eq!(a.to_string(), "a\nb\nc\nd");
+        assert_eq!(a.dedup_lines(0..5), 0);
+        assert_eq!(a.dedup_lines(7..9), 0);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::RipString;
+use alloc::vec::Vec;
+use core::ops::Range;
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineBreak {
+    /// A line must break at this index.
+    Mandatory,
+    /// A line is allowed to break at this index.
+    Allowed,
+}
+
+impl From<BreakOpportunity> for LineBreak {
+    fn from(val: BreakOpportunity) -> Self {
+        match val {
+            BreakOpportunity::Mandatory => LineBreak::Mandatory,
+            BreakOpportunity::Allowed => LineBreak::Allowed,
+        }
+    }
+}
+
+impl RipString {
+    /// Returns the line break opportunities (UAX #14) inside the range. Each index is the
+    /// position of the first unit of the next line.
+    pub fn break_opportunities(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (usize, LineBreak)> {
+        let (text, offsets) = self.text_with_offsets(range.clone());
+        // The end of the range is only a forced break if it is the end of the text.
+        let last = if range.end >= self.len() {
+            offsets.len()
+        } else {
+            offsets.len() - 1
+        };
+
+        linebreaks(&text)
+            .filter_map(|(byte, opportunity)| {
+                let unit = offsets.binary_search(&byte).ok()?;
+                if unit < last {
+                    Some((range.start + unit, opportunity.into()))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LineBreak, RipString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_break_opportunities() {
+        let a = RipString::from("Hello world\nмир 🏡");
+        assert_eq!(
+            a.break_opportunities(0..17).collect::<Vec<_>>(),
+            [
+                (6, LineBreak::Allowed),
+                (12, LineBreak::Mandatory),
+                (16, LineBreak::Allowed),
+                (17, LineBreak::Mandatory)
+            ]
+        );
+        assert_eq!(
+            a.break_opportunities(2..11).collect::<Vec<_>>(),
+            [(6, LineBreak::Allowed)]
+        );
+    }
+}
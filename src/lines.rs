@@ -0,0 +1,250 @@
+use crate::segment::Segment;
+use crate::splitter::MAX_BLOCK_SIZE;
+use crate::RipString;
+use alloc::vec::Vec;
+use core::mem;
+use core::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Mixed { lf: usize, crlf: usize },
+}
+
+impl RipString {
+    /// Reports the line terminator convention of the text. Text without line
+    /// terminators is reported as `Lf`.
+    pub fn detect_line_endings(&self) -> LineEnding {
+        match self.count_line_endings() {
+            (_, 0) => LineEnding::Lf,
+            (0, _) => LineEnding::CrLf,
+            (lf, crlf) => LineEnding::Mixed { lf, crlf },
+        }
+    }
+
+    /// Returns the number of bare `\n` and of `\r\n` line terminators.
+    pub(crate) fn count_line_endings(&self) -> (usize, usize) {
+        let mut lf = 0;
+        let mut crlf = 0;
+        for node in &self.nodes {
+            for pos in node.newlines() {
+                let index = node.index() + pos;
+                if index > 0 && self.unit_is(index - 1, "\r") {
+                    crlf += 1;
+                } else {
+                    lf += 1;
+                }
+            }
+        }
+        (lf, crlf)
+    }
+
+    pub fn line_count(&self) -> usize {
+        1 + self
+            .nodes
+            .iter()
+            .map(|node| node.newlines().count())
+            .sum::<usize>()
+    }
+
+    /// Returns the index of the first unit of the given line.
+    pub fn line_to_index(&self, line: usize) -> Option<usize> {
+        if line == 0 {
+            return Some(0);
+        }
+
+        let mut remaining = line;
+        for node in &self.nodes {
+            for pos in node.newlines() {
+                remaining -= 1;
+                if remaining == 0 {
+                    return Some(node.index() + pos + 1);
+                }
+            }
+        }
+        None
+    }
+
+    /// Rewrites all line terminators to the given convention. `Mixed` leaves the
+    /// text unchanged.
+    pub fn convert_line_endings(&mut self, ending: LineEnding) {
+        let crlf = match ending {
+            LineEnding::Lf => false,
+            LineEnding::CrLf => true,
+            LineEnding::Mixed { .. } => return,
+        };
+
+        let mut nodes: Vec<Segment> = Vec::with_capacity(self.nodes.len());
+        let mut prev_cr = false;
+        for node in mem::take(&mut self.nodes) {
+            if node.len() == 0 {
+                continue;
+            }
+            let ends_with_cr = node.tp().is_char(node.len() - 1, '\r');
+            let first_newline = node.newlines().next();
+
+            if first_newline.is_some() {
+                // The `\r` of a `\r\n` split between two segments is dropped from the previous one.
+                if !crlf && prev_cr && first_newline == Some(0) {
+                    if let Some(prev) = nodes.last_mut() {
+                        let last = prev.len() - 1;
+                        prev.modify(|tp| tp.split(last));
+                    }
+                }
+
+                let mut tp = node.tp().convert_line_endings(crlf, prev_cr);
+                if tp.len() > MAX_BLOCK_SIZE {
+                    let tail = tp.split(tp.len() / 2);
+                    nodes.push(Segment::new(0, tp));
+                    nodes.push(Segment::new(0, tail));
+                } else {
+                    nodes.push(Segment::new(0, tp));
+                }
+            } else {
+                nodes.push(node);
+            }
+            prev_cr = ends_with_cr;
+        }
+
+        nodes.retain(|node| node.len() > 0);
+        self.reset_nodes(nodes);
+    }
+
+    /// Returns the line containing the given index.
+    pub fn index_to_line(&self, index: usize) -> usize {
+        let mut line = 0;
+        for node in &self.nodes {
+            if node.index() >= index {
+                break;
+            }
+            line += node
+                .newlines()
+                .take_while(|pos| node.index() + pos < index)
+                .count();
+        }
+        line
+    }
+
+    /// Returns the range of the given line without its line terminator.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = self.line_to_index(line)?;
+        Some(start..self.line_end(start).0)
+    }
+
+    /// Returns the end of the line starting at `start` without its line terminator and the
+    /// start of the next line, if any.
+    pub(crate) fn line_end(&self, start: usize) -> (usize, Option<usize>) {
+        let seg_index = self.find_segment(start);
+        let newline = self.nodes[seg_index..].iter().find_map(|node| {
+            node.newlines()
+                .map(|pos| node.index() + pos)
+                .find(|pos| *pos >= start)
+        });
+
+        match newline {
+            Some(newline) => {
+                let end = if newline > start && self.unit_is(newline - 1, "\r") {
+                    newline - 1
+                } else {
+                    newline
+                };
+                (end, Some(newline + 1))
+            }
+            None => (self.len(), None),
+        }
+    }
+
+    pub(crate) fn unit_is(&self, index: usize, unit: &str) -> bool {
+        let mut found = false;
+        self.for_each_unit(index..index + 1, |u| found = u == unit);
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LineEnding, RipString};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_lines() {
+        let a = RipString::from("Hello\r\nмир\n🏡\n");
+        assert_eq!(a.line_count(), 4);
+        assert_eq!(a.line_to_index(0), Some(0));
+        assert_eq!(a.line_to_index(1), Some(7));
+        assert_eq!(a.line_to_index(2), Some(11));
+        assert_eq!(a.line_to_index(3), Some(13));
+        assert_eq!(a.line_to_index(4), None);
+        assert_eq!(a.index_to_line(0), 0);
+        assert_eq!(a.index_to_line(6), 0);
+        assert_eq!(a.index_to_line(7), 1);
+        assert_eq!(a.index_to_line(12), 2);
+        assert_eq!(a.index_to_line(13), 3);
+        assert_eq!(a.line_range(0), Some(0..5));
+        assert_eq!(a.line_range(1), Some(7..10));
+        assert_eq!(a.line_range(2), Some(11..12));
+        assert_eq!(a.line_range(3), Some(13..13));
+
+        assert_eq!(RipString::new().line_count(), 1);
+        assert_eq!(RipString::new().line_range(0), Some(0..0));
+    }
+
+    #[test]
+    fn test_detect_line_endings() {
+        assert_eq!(RipString::new().detect_line_endings(), LineEnding::Lf);
+        assert_eq!(
+            RipString::from("a\nб\n").detect_line_endings(),
+            LineEnding::Lf
+        );
+        assert_eq!(
+            RipString::from("a\r\nб\r\n").detect_line_endings(),
+            LineEnding::CrLf
+        );
+
+        let mut a = RipString::from("a\r\nb\n");
+        a.edit(5..5, "я\r");
+        a.edit(7..7, "\n");
+        assert_eq!(a.to_string(), "a\r\nb\nя\r\n");
+        assert_eq!(
+            a.detect_line_endings(),
+            LineEnding::Mixed { lf: 1, crlf: 2 }
+        );
+    }
+
+    #[test]
+    fn test_convert_line_endings() {
+        let mut a = RipString::from("a\r\nb\nя\n\n🏡\r\n");
+        a.convert_line_endings(LineEnding::CrLf);
+        assert_eq!(a.to_string(), "a\r\nb\r\nя\r\n\r\n🏡\r\n");
+        assert_eq!(a.len(), 14);
+        a.convert_line_endings(LineEnding::Lf);
+        assert_eq!(a.to_string(), "a\nb\nя\n\n🏡\n");
+        assert_eq!(a.line_to_index(4), Some(7));
+
+        let mut a = RipString::from("ab\r");
+        a.edit(3..3, "\nя\n");
+        a.convert_line_endings(LineEnding::Lf);
+        assert_eq!(a.to_string(), "ab\nя\n");
+        assert_eq!(a.len(), 5);
+
+        let mut a = RipString::from("\n".repeat(1000).as_str());
+        a.convert_line_endings(LineEnding::CrLf);
+        assert_eq!(a.to_string(), "\r\n".repeat(1000));
+        assert_eq!(a.detect_line_endings(), LineEnding::CrLf);
+    }
+
+    #[test]
+    fn test_crlf_across_segments() {
+        let mut a = RipString::from("ab\r");
+        a.edit(3..3, "я\nc");
+        a.edit(3..4, "");
+        assert_eq!(a.to_string(), "ab\r\nc");
+        assert_eq!(a.line_count(), 2);
+        assert_eq!(a.line_range(0), Some(0..2));
+        assert_eq!(a.line_range(1), Some(4..5));
+        assert_eq!(a.index_to_line(3), 0);
+        assert_eq!(a.detect_line_endings(), LineEnding::CrLf);
+        assert!(!a.is_grapheme_boundary(3));
+    }
+}
@@ -0,0 +1,126 @@
+//! Node.js bindings. All indices are UTF-16 code units, as in JavaScript strings.
+
+use crate::RipString;
+use alloc::string::{String, ToString};
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Error, Result, Task};
+use napi_derive::napi;
+// The generated glue expects the std prelude.
+use std::format;
+use std::prelude::v1::*;
+
+#[napi(js_name = "RipString")]
+pub struct NodeRipString {
+    rip: RipString,
+}
+
+#[napi]
+impl NodeRipString {
+    #[napi(constructor)]
+    pub fn new(text: String) -> NodeRipString {
+        NodeRipString {
+            rip: RipString::from(text.as_str()),
+        }
+    }
+
+    /// Length in UTF-16 code units.
+    #[napi(getter)]
+    pub fn length(&self) -> u32 {
+        self.rip.index_to_utf16(self.rip.len()) as u32
+    }
+
+    /// Replaces the text between UTF-16 offsets. Offsets inside a grapheme cluster are
+    /// moved to its start. Throws if `start` is after `end`.
+    #[napi]
+    pub fn edit(&mut self, start: u32, end: u32, text: String) -> Result<()> {
+        let range = self.range(start, end);
+        self.rip
+            .try_edit(range, &text)
+            .map_err(|err| Error::from_reason(err.to_string()))
+    }
+
+    #[napi]
+    pub fn slice(&self, start: u32, end: u32) -> String {
+        self.rip.collect_range(self.range(start, end))
+    }
+
+    #[napi]
+    pub fn line_count(&self) -> u32 {
+        self.rip.line_count() as u32
+    }
+
+    /// Returns the UTF-16 offset of the start of the line.
+    #[napi]
+    pub fn line_start(&self, line: u32) -> Option<u32> {
+        self.rip
+            .line_to_index(line as usize)
+            .map(|index| self.rip.index_to_utf16(index) as u32)
+    }
+
+    /// Returns the line containing the UTF-16 offset.
+    #[napi]
+    pub fn line_at(&self, offset: u32) -> u32 {
+        self.rip
+            .index_to_line(self.rip.utf16_to_index(offset as usize)) as u32
+    }
+
+    #[napi(js_name = "toString")]
+    pub fn to_js_string(&self) -> String {
+        self.rip.to_string()
+    }
+
+    fn range(&self, start: u32, end: u32) -> core::ops::Range<usize> {
+        self.rip.utf16_to_index(start as usize)..self.rip.utf16_to_index(end as usize)
+    }
+}
+
+/// Reads a UTF-8 file on the libuv thread pool.
+pub struct LoadFile {
+    path: String,
+}
+
+impl Task for LoadFile {
+    type Output = String;
+    type JsValue = NodeRipString;
+
+    fn compute(&mut self) -> Result<String> {
+        std::fs::read_to_string(&self.path).map_err(|err| Error::from_reason(err.to_string()))
+    }
+
+    fn resolve(&mut self, _env: Env, text: String) -> Result<NodeRipString> {
+        Ok(NodeRipString::new(text))
+    }
+}
+
+/// Loads a file into a new `RipString` without blocking the event loop.
+#[napi]
+pub fn load_file(path: String) -> AsyncTask<LoadFile> {
+    AsyncTask::new(LoadFile { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::node::{LoadFile, NodeRipString};
+    use napi::Task;
+
+    #[test]
+    fn test_utf16_indices() {
+        let mut a = NodeRipString::new("a🏡 мир\nok".into());
+        assert_eq!(a.length(), 10);
+        assert_eq!(a.slice(1, 3), "🏡");
+        assert_eq!(a.line_start(1), Some(8));
+        assert_eq!(a.line_at(9), 1);
+
+        a.edit(2, 7, "!".into()).unwrap();
+        assert_eq!(a.to_js_string(), "a!\nok");
+        let err = a.edit(3, 1, "?".into()).unwrap_err();
+        assert_eq!(err.reason, "Range starts at 3 after its end 1");
+        assert_eq!(a.to_js_string(), "a!\nok");
+        assert_eq!(a.line_start(2), None);
+
+        let mut task = LoadFile {
+            path: "Cargo.toml".into(),
+        };
+        assert!(task.compute().unwrap().contains("rip_str"));
+    }
+}
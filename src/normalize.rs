@@ -0,0 +1,116 @@
+use crate::segment::Segment;
+use crate::splitter::Splitter;
+use crate::RipString;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::mem;
+use unicode_normalization::{is_nfc_quick, is_nfd_quick, IsNormalized, UnicodeNormalization};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+}
+
+impl NormalizationForm {
+    pub(crate) fn is_normalized<I: Iterator<Item = char>>(self, chars: I) -> bool {
+        let quick = match self {
+            NormalizationForm::Nfc => is_nfc_quick(chars),
+            NormalizationForm::Nfd => is_nfd_quick(chars),
+        };
+        quick == IsNormalized::Yes
+    }
+
+    pub(crate) fn normalize<I: Iterator<Item = char>>(self, chars: I) -> String {
+        match self {
+            NormalizationForm::Nfc => chars.nfc().collect(),
+            NormalizationForm::Nfd => chars.nfd().collect(),
+        }
+    }
+}
+
+impl RipString {
+    /// Renormalizes the text. Only segments which are not already in the given form are
+    /// rebuilt.
+    pub fn normalize(&mut self, form: NormalizationForm) {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        for node in mem::take(&mut self.nodes) {
+            if form.is_normalized(node.chars()) {
+                nodes.push(node);
+            } else {
+                let text = form.normalize(node.chars());
+                nodes.extend(Splitter::new(&text, self.segmenter).map(|tp| Segment::new(0, tp)));
+            }
+        }
+        self.reset_nodes(nodes);
+    }
+
+    /// Enables NFC normalization of all inserted text. Only the inserted text is
+    /// normalized; use `normalize` to compose across edit boundaries.
+    pub fn set_normalize_on_insert(&mut self, enabled: bool) {
+        self.normalize_on_insert = enabled;
+    }
+
+    pub fn normalize_on_insert(&self) -> bool {
+        self.normalize_on_insert
+    }
+
+    /// Compares the canonical (NFC) forms of both strings without materializing them.
+    pub fn eq_normalized(&self, other: &RipString) -> bool {
+        self.chars().nfc().eq(other.chars().nfc())
+    }
+
+    /// Orders the canonical (NFC) forms of both strings by code point.
+    pub fn cmp_normalized(&self, other: &RipString) -> Ordering {
+        self.chars().nfc().cmp(other.chars().nfc())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NormalizationForm, RipString};
+    use alloc::string::ToString;
+    use core::cmp::Ordering;
+
+    #[test]
+    fn test_normalize_on_insert() {
+        let mut a = RipString::from("e\u{301}");
+        assert!(!a.normalize_on_insert());
+        a.set_normalize_on_insert(true);
+        a.edit(1..1, " Cafe\u{301}");
+        a.edit(6..6, "и\u{306}");
+        assert_eq!(a.to_string(), "e\u{301} Caféй");
+        a.replace(1..5, "A\u{30a}");
+        assert_eq!(a.to_string(), "e\u{301}Åéй");
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut a = RipString::from("Cafe\u{301} и\u{306}од");
+        a.normalize(NormalizationForm::Nfc);
+        assert_eq!(a.to_string(), "Café йод");
+        assert_eq!(a.len(), 8);
+
+        a.normalize(NormalizationForm::Nfd);
+        assert_eq!(a.to_string(), "Cafe\u{301} и\u{306}од");
+        assert_eq!(a.len(), 8);
+
+        a.edit(8..8, "!");
+        assert_eq!(a.to_string(), "Cafe\u{301} и\u{306}од!");
+    }
+
+    #[test]
+    fn test_eq_normalized() {
+        let composed = RipString::from("Café");
+        let decomposed = RipString::from("Cafe\u{301}");
+        assert!(composed.eq_normalized(&decomposed));
+        assert_eq!(composed.cmp_normalized(&decomposed), Ordering::Equal);
+
+        let other = RipString::from("Cafe");
+        assert!(!composed.eq_normalized(&other));
+        assert_eq!(other.cmp_normalized(&decomposed), Ordering::Less);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::segment::SegmentType;
+use crate::RipString;
+use core::cmp::min;
+
+impl RipString {
+    /// Returns the byte offset of the unit at `index`.
+    pub fn index_to_byte(&self, index: usize) -> usize {
+        if index >= self.len() {
+            return self.len_bytes();
+        }
+        let node = &self.nodes[self.find_segment(index)];
+        let mut offset = node.byte_index();
+        node.for_each_unit(0..index - node.index(), |unit| offset += unit.len());
+        offset
+    }
+
+    /// Returns the index of the unit containing the byte offset, or the length of the
+    /// text if the offset is past its end.
+    pub fn byte_to_index(&self, byte: usize) -> usize {
+        let node = match self.nodes.get(self.find_byte_segment(byte)) {
+            Some(node) => node,
+            None => return self.len(),
+        };
+        let mut start = node.byte_index();
+        let mut unit = 0;
+        node.for_each_unit(0..node.len(), |text| {
+            start += text.len();
+            if start <= byte {
+                unit += 1;
+            }
+        });
+        node.index() + unit
+    }
+
+    /// Copies the UTF-8 bytes starting at byte `offset` into `buf` and returns the number
+    /// of bytes copied, which is 0 at or past the end of the text. The first segment is
+    /// found by a binary search over the segment byte offsets.
+    pub fn read_at(&self, offset: usize, buf: &mut [u8]) -> usize {
+        let first = self.find_byte_segment(offset);
+        let mut read = 0;
+        for node in self.nodes.iter().skip(first) {
+            if read == buf.len() {
+                break;
+            }
+            let skip = offset.saturating_sub(node.byte_index());
+            read += read_segment(node.tp(), skip, &mut buf[read..]);
+        }
+        read
+    }
+
+    /// Returns the position of the segment containing the byte offset, or the number of
+    /// segments if the offset is past the end.
+    fn find_byte_segment(&self, byte: usize) -> usize {
+        self.nodes
+            .partition_point(|node| node.byte_index() + node.byte_len() <= byte)
+    }
+
+    /// Converts a unit index to an offset in the encoding measured by `seg_len` and
+    /// `unit_len`.
+    pub(crate) fn index_to_offset(
+        &self,
+        index: usize,
+        seg_len: fn(&SegmentType) -> usize,
+        unit_len: fn(&str) -> usize,
+    ) -> usize {
+        let mut offset = 0;
+        for node in &self.nodes {
+            if node.index() + node.len() > index {
+                node.for_each_unit(0..index - node.index(), |unit| offset += unit_len(unit));
+                return offset;
+            }
+            offset += seg_len(node.tp());
+        }
+        offset
+    }
+
+    /// Converts an offset in the encoding measured by `seg_len` and `unit_len` to the
+    /// index of the unit containing it.
+    pub(crate) fn offset_to_index(
+        &self,
+        offset: usize,
+        seg_len: fn(&SegmentType) -> usize,
+        unit_len: fn(&str) -> usize,
+    ) -> usize {
+        let mut start = 0;
+        for node in &self.nodes {
+            let len = seg_len(node.tp());
+            if start + len > offset {
+                let mut unit = 0;
+                node.for_each_unit(0..node.len(), |text| {
+                    start += unit_len(text);
+                    if start <= offset {
+                        unit += 1;
+                    }
+                });
+                return node.index() + unit;
+            }
+            start += len;
+        }
+        self.len()
+    }
+}
+
+/// Copies the bytes of the segment from byte `skip` on into `buf` and returns the number
+/// of bytes copied. UTF-8 chars are encoded straight into `buf` unless they are cut.
+fn read_segment(tp: &SegmentType, skip: usize, buf: &mut [u8]) -> usize {
+    match tp {
+        SegmentType::Ascii(val) => copy_piece(val, skip, buf),
+        SegmentType::Utf8(val) => {
+            let mut pos = 0;
+            let mut read = 0;
+            for ch in val {
+                if read == buf.len() {
+                    break;
+                }
+                let len = ch.len_utf8();
+                if pos >= skip && buf.len() - read >= len {
+                    ch.encode_utf8(&mut buf[read..]);
+                    read += len;
+                } else if pos + len > skip {
+                    let mut tmp = [0; 4];
+                    let bytes = ch.encode_utf8(&mut tmp).as_bytes();
+                    read += copy_piece(bytes, skip.saturating_sub(pos), &mut buf[read..]);
+                }
+                pos += len;
+            }
+            read
+        }
+        SegmentType::Unicode(val) => {
+            let mut pos = 0;
+            let mut read = 0;
+            for grapheme in val {
+                if read == buf.len() {
+                    break;
+                }
+                if pos + grapheme.len() > skip {
+                    let skip = skip.saturating_sub(pos);
+                    read += copy_piece(grapheme.as_bytes(), skip, &mut buf[read..]);
+                }
+                pos += grapheme.len();
+            }
+            read
+        }
+    }
+}
+
+/// Copies `piece` from byte `skip` on into `buf`, as much as fits.
+fn copy_piece(piece: &[u8], skip: usize, buf: &mut [u8]) -> usize {
+    let skip = min(skip, piece.len());
+    let count = min(piece.len() - skip, buf.len());
+    buf[..count].copy_from_slice(&piece[skip..skip + count]);
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::ToString;
+    use core::cmp::min;
+
+    #[test]
+    fn test_byte_offsets() {
+        let a = RipString::from("ab мир 🏡e\u{301}!");
+        let bytes = [0, 1, 2, 3, 5, 7, 9, 10, 14, 17, 18];
+        for (index, byte) in bytes.iter().enumerate() {
+            assert_eq!(a.index_to_byte(index), *byte);
+            assert_eq!(a.byte_to_index(*byte), index);
+        }
+        assert_eq!(a.byte_to_index(4), 3);
+        assert_eq!(a.byte_to_index(16), 8);
+        assert_eq!(a.byte_to_index(100), 10);
+    }
+
+    #[test]
+    fn test_read_at() {
+        let text = "ab мир 🏡e\u{301}!";
+        let a = RipString::from(text);
+        let mut buf = [0; 5];
+        for offset in 0..text.len() {
+            let read = a.read_at(offset, &mut buf);
+            let expected = &text.as_bytes()[offset..min(offset + 5, text.len())];
+            assert_eq!(&buf[..read], expected);
+        }
+        assert_eq!(a.read_at(text.len(), &mut buf), 0);
+        assert_eq!(a.read_at(100, &mut buf), 0);
+    }
+
+    #[test]
+    fn test_read_at_segments() {
+        let mut text = "ab мир 🏡e\u{301}!\n".repeat(400);
+        let mut a = RipString::from(text.as_str());
+        a.insert(700, "xyz");
+        a.remove(3000..3100);
+        text = a.to_string();
+        assert_eq!(a.validate(), Ok(()));
+
+        let mut buf = [0; 1500];
+        for offset in (0..text.len()).step_by(97) {
+            let read = a.read_at(offset, &mut buf);
+            let expected = &text.as_bytes()[offset..min(offset + buf.len(), text.len())];
+            assert_eq!(&buf[..read], expected);
+            let byte = a.index_to_byte(a.byte_to_index(offset));
+            assert!(byte <= offset && text.is_char_boundary(byte));
+        }
+        assert_eq!(a.index_to_byte(a.len()), text.len());
+    }
+}
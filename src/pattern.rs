@@ -0,0 +1,414 @@
+use crate::{RipSlice, RipString};
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+use core::cmp::{max, min};
+use core::ops::Range;
+
+/// Something to search for: a `char`, a string, a set of chars or a `char` predicate.
+pub trait Pattern {
+    /// Returns the byte range of the first match in `haystack`.
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>>;
+
+    /// Returns the maximum byte length of a match. The text is then searched segment by
+    /// segment, keeping that many bytes around chunk boundaries. With `None` the whole
+    /// text is searched at once.
+    fn max_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl Pattern for char {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        let start = haystack.find(*self)?;
+        Some(start..start + self.len_utf8())
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(self.len_utf8())
+    }
+}
+
+impl Pattern for &str {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        let start = haystack.find(*self)?;
+        Some(start..start + self.len())
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl Pattern for &String {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        self.as_str().find_in(haystack)
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl Pattern for &[char] {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        let set = *self;
+        (|ch: char| set.contains(&ch)).find_in(haystack)
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        self.iter().map(|ch| ch.len_utf8()).max()
+    }
+}
+
+impl<F: FnMut(char) -> bool> Pattern for F {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        haystack
+            .char_indices()
+            .find(|(_, ch)| self(*ch))
+            .map(|(start, ch)| start..start + ch.len_utf8())
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(char::MAX.len_utf8())
+    }
+}
+
+impl RipString {
+    /// Returns the range of the first match of the pattern. Matches that start or end
+    /// inside a grapheme cluster are skipped.
+    pub fn find<P: Pattern>(&self, pattern: P) -> Option<Range<usize>> {
+        self.match_ranges(0..self.len(), pattern, 1).pop()
+    }
+
+    /// Returns the ranges of all non-overlapping matches of the pattern.
+    pub fn find_all<P: Pattern>(&self, pattern: P) -> Vec<Range<usize>> {
+        self.match_ranges(0..self.len(), pattern, usize::MAX)
+    }
+
+    /// Returns the ranges of the text between matches of the pattern.
+    pub fn split<P: Pattern>(&self, pattern: P) -> vec::IntoIter<Range<usize>> {
+        let mut start = 0;
+        let mut parts = Vec::new();
+        for found in self.match_ranges(0..self.len(), pattern, usize::MAX) {
+            parts.push(start..found.start);
+            start = found.end;
+        }
+        parts.push(start..self.len());
+        parts.into_iter()
+    }
+
+    /// Replaces all matches of the pattern and returns the number of replacements.
+    pub fn replace_all<P: Pattern>(&mut self, pattern: P, with: &str) -> usize {
+        self.replacen(pattern, with, usize::MAX)
+    }
+
+    /// Replaces the first match of the pattern and returns the number of replacements.
+    pub fn replace_first<P: Pattern>(&mut self, pattern: P, with: &str) -> usize {
+        self.replacen(pattern, with, 1)
+    }
+
+    /// Replaces the first `n` matches of the pattern and returns the number of
+    /// replacements.
+    pub fn replacen<P: Pattern>(&mut self, pattern: P, with: &str, n: usize) -> usize {
+        let edits = self
+            .match_ranges(0..self.len(), pattern, n)
+            .into_iter()
+            .map(|range| (range, with))
+            .collect::<Vec<_>>();
+        self.apply_edits(&edits);
+        edits.len()
+    }
+
+    /// Returns true if the text starts with `prefix` and the prefix ends on a unit
+    /// boundary.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.prefix_units(prefix).is_some()
+    }
+
+    /// Returns true if the text ends with `suffix` and the suffix starts on a unit
+    /// boundary.
+    pub fn ends_with(&self, suffix: &str) -> bool {
+        self.suffix_units(suffix).is_some()
+    }
+
+    /// Returns a view of the text after `prefix`, or `None` if it does not start with it.
+    pub fn strip_prefix(&self, prefix: &str) -> Option<RipSlice<'_>> {
+        self.prefix_units(prefix).map(|units| self.slice(units..))
+    }
+
+    /// Returns a view of the text before `suffix`, or `None` if it does not end with it.
+    pub fn strip_suffix(&self, suffix: &str) -> Option<RipSlice<'_>> {
+        self.suffix_units(suffix)
+            .map(|units| self.slice(..self.len() - units))
+    }
+
+    /// Returns the number of units `prefix` is made of at the start of the text.
+    fn prefix_units(&self, prefix: &str) -> Option<usize> {
+        let mut rest = prefix;
+        let mut units = 0;
+        for node in &self.nodes {
+            if rest.is_empty() {
+                break;
+            }
+            let mut matched = true;
+            let stop = node.tp().find_unit(0..node.len(), |unit| {
+                match rest.strip_prefix(unit) {
+                    Some(tail) => {
+                        rest = tail;
+                        units += 1;
+                    }
+                    None => matched = false,
+                }
+                !matched || rest.is_empty()
+            });
+            if !matched || stop.is_some() {
+                break;
+            }
+        }
+        if rest.is_empty() {
+            Some(units)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of units `suffix` is made of at the end of the text.
+    fn suffix_units(&self, suffix: &str) -> Option<usize> {
+        let mut rest = suffix;
+        let mut units = 0;
+        for node in self.nodes.iter().rev() {
+            if rest.is_empty() {
+                break;
+            }
+            let mut matched = true;
+            let stop = node.tp().rfind_unit(0..node.len(), |unit| {
+                match rest.strip_suffix(unit) {
+                    Some(head) => {
+                        rest = head;
+                        units += 1;
+                    }
+                    None => matched = false,
+                }
+                !matched || rest.is_empty()
+            });
+            if !matched || stop.is_some() {
+                break;
+            }
+        }
+        if rest.is_empty() {
+            Some(units)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ranges of up to `limit` matches within `range`. The text is copied
+    /// one segment at a time, and searching stops once `limit` matches are found.
+    pub(crate) fn match_ranges<P: Pattern>(
+        &self,
+        range: Range<usize>,
+        mut pattern: P,
+        limit: usize,
+    ) -> Vec<Range<usize>> {
+        let overlap = pattern.max_len().unwrap_or(usize::MAX);
+        let mut window = Window::new(range.start);
+        let mut found = Vec::new();
+        let mut from = 0;
+        let mut seg_index = self.find_segment(range.start);
+        let mut done = false;
+        while !done && found.len() < limit {
+            if let Some(node) = self.nodes.get(seg_index) {
+                let start = range.start.saturating_sub(node.index());
+                let end = min(range.end.saturating_sub(node.index()), node.len());
+                if start < end {
+                    node.for_each_unit(start..end, |unit| window.push(unit));
+                }
+                seg_index += 1;
+            }
+            done = seg_index >= self.nodes.len() || self.nodes[seg_index].index() >= range.end;
+
+            let text = &window.text;
+            while from <= text.len() && found.len() < limit {
+                let range = match pattern.find_in(&text[from..]) {
+                    Some(range) => from + range.start..from + range.end,
+                    None => {
+                        from = max(
+                            from,
+                            window.boundary_before(text.len().saturating_sub(overlap)),
+                        );
+                        break;
+                    }
+                };
+                // A longer match may still start before the end of the window.
+                if !done && range.start.saturating_add(overlap) >= text.len() {
+                    break;
+                }
+                match (window.unit_at(range.start), window.unit_at(range.end)) {
+                    (Some(start), Some(end)) => {
+                        found.push(window.base + start..window.base + end);
+                        from = if start == end {
+                            window.unit_start(end + 1)
+                        } else {
+                            range.end
+                        };
+                    }
+                    _ => {
+                        from = range.start
+                            + text[range.start..].chars().next().map_or(1, char::len_utf8);
+                    }
+                }
+            }
+            from -= window.trim(min(from, text.len().saturating_sub(overlap)));
+        }
+        found
+    }
+}
+
+/// Text of consecutive units being searched, with the byte offset of every unit.
+struct Window {
+    text: String,
+    offsets: Vec<usize>,
+    /// Index of the first unit in the window.
+    base: usize,
+}
+
+impl Window {
+    fn new(base: usize) -> Window {
+        Window {
+            text: String::new(),
+            offsets: Vec::new(),
+            base,
+        }
+    }
+
+    fn push(&mut self, unit: &str) {
+        self.offsets.push(self.text.len());
+        self.text.push_str(unit);
+    }
+
+    /// Returns the unit starting at the byte offset; the end of the text counts as the
+    /// start of the unit after the last one.
+    fn unit_at(&self, byte: usize) -> Option<usize> {
+        if byte == self.text.len() {
+            return Some(self.offsets.len());
+        }
+        self.offsets.binary_search(&byte).ok()
+    }
+
+    /// Returns the byte offset of the unit, `text.len()` for the unit after the last one
+    /// and `text.len() + 1` past it.
+    fn unit_start(&self, unit: usize) -> usize {
+        match self.offsets.get(unit) {
+            Some(offset) => *offset,
+            None if unit == self.offsets.len() => self.text.len(),
+            None => self.text.len() + 1,
+        }
+    }
+
+    /// Returns the start of the unit containing the byte offset.
+    fn boundary_before(&self, byte: usize) -> usize {
+        let unit = self.offsets.partition_point(|offset| *offset <= byte);
+        self.offsets.get(unit.wrapping_sub(1)).copied().unwrap_or(0)
+    }
+
+    /// Drops the units before the one containing `byte` and returns the number of bytes
+    /// removed.
+    fn trim(&mut self, byte: usize) -> usize {
+        let unit = self
+            .offsets
+            .partition_point(|offset| *offset <= byte)
+            .saturating_sub(1);
+        let cut = match self.offsets.get(unit) {
+            Some(cut) if unit > 0 => *cut,
+            _ => return 0,
+        };
+        self.text.drain(..cut);
+        self.offsets.drain(..unit);
+        self.offsets.iter_mut().for_each(|offset| *offset -= cut);
+        self.base += unit;
+        cut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_find() {
+        let a = RipString::from("cafe\u{301} café, мир\r\n!");
+        assert_eq!(a.find('é'), Some(8..9));
+        assert_eq!(a.find("e"), None);
+        assert_eq!(a.find(&['м', ','][..]), Some(9..10));
+        assert_eq!(a.find(char::is_whitespace), Some(4..5));
+        assert_eq!(a.find("\n"), Some(15..16));
+        assert_eq!(
+            a.find_all(|ch: char| ch.is_ascii_punctuation()),
+            [9..10, 16..17]
+        );
+        assert_eq!(a.find_all(""), (0..=17).map(|i| i..i).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_find_across_segments() {
+        let text = "ab мир 🏡e\u{301}!\n".repeat(400);
+        let a = RipString::from(text.as_str());
+        assert!(a.nodes.len() > 3);
+
+        let found = a.find_all("!\nab");
+        assert_eq!(found.len(), 399);
+        assert!(found
+            .iter()
+            .all(|range| a.substring(range.clone()) == "!\nab"));
+        assert_eq!(a.find_all("🏡e\u{301}").len(), 400);
+        assert_eq!(a.find_all("🏡e").len(), 0);
+        assert_eq!(a.find_all('м').len(), 400);
+        assert_eq!(a.find_all(&['!', '\n'][..]).len(), 800);
+        assert_eq!(a.find_all("").len(), a.len() + 1);
+        assert_eq!(a.find("мир 🏡e\u{301}!\nab мир"), Some(3..17));
+    }
+
+    #[test]
+    fn test_split_replace() {
+        let mut a = RipString::from("a, b,,c");
+        assert_eq!(a.split(',').collect::<Vec<_>>(), [0..1, 2..4, 5..5, 6..7]);
+        let mut parts = RipString::new().split(',');
+        assert_eq!(parts.next(), Some(0..0));
+        assert_eq!(parts.next(), None);
+
+        assert_eq!(a.replace_all(", ", " & "), 1);
+        assert_eq!(a.replace_all(&[',', '&'][..], "+"), 3);
+        assert_eq!(a.to_string(), "a + b++c");
+
+        let mut a = RipString::from("мир, мир 🏡 мир!");
+        assert_eq!(a.replace_first("мир", "peace"), 1);
+        assert_eq!(a.to_string(), "peace, мир 🏡 мир!");
+        assert_eq!(a.replacen("мир", "🌋", 5), 2);
+        assert_eq!(a.to_string(), "peace, 🌋 🏡 🌋!");
+        assert_eq!(a.replacen('e', "", 0), 0);
+        assert_eq!(a.replace_first("мир", "x"), 0);
+        assert_eq!(a.to_string(), "peace, 🌋 🏡 🌋!");
+    }
+
+    #[test]
+    fn test_prefix_suffix() {
+        let text = "мир 🏡 e\u{301}\n".repeat(300);
+        let a = RipString::from(text.as_str());
+        let prefix = "мир 🏡 e\u{301}\n".repeat(120) + "ми";
+        assert!(a.starts_with(&prefix));
+        assert!(a.starts_with(""));
+        assert!(!a.starts_with("мир!"));
+        assert!(!a.starts_with("мир 🏡 e"));
+        assert!(a.ends_with(" e\u{301}\n"));
+        assert!(!a.ends_with("\u{301}\n"));
+        assert!(!RipString::new().starts_with("a"));
+
+        let b = RipString::from("Hello мир");
+        assert_eq!(b.strip_prefix("Hello ").unwrap().to_string(), "мир");
+        assert_eq!(b.strip_suffix("ир").unwrap().to_string(), "Hello м");
+        assert!(b.strip_prefix("мир").is_none());
+        assert_eq!(b.strip_suffix("").unwrap().len(), 9);
+    }
+}
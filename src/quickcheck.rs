@@ -0,0 +1,100 @@
+//! QuickCheck generation and shrinking of ropes and edit operations.
+
+use crate::edit_op::FRAGMENTS;
+use crate::{EditOp, RipString};
+use ::quickcheck::{Arbitrary, Gen};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+
+fn fragments(g: &mut Gen, max: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..usize::arbitrary(g) % (max + 1) {
+        text.push_str(g.choose(FRAGMENTS).unwrap());
+    }
+    text
+}
+
+impl Arbitrary for RipString {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let size = g.size();
+        RipString::from(fragments(g, size).as_str())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            self.to_string()
+                .shrink()
+                .map(|text| RipString::from(text.as_str())),
+        )
+    }
+}
+
+impl Arbitrary for EditOp {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let size = g.size();
+        let start = usize::arbitrary(g) % (size * 2 + 1);
+        let range = start..start + usize::arbitrary(g) % (size / 4 + 1);
+        match usize::arbitrary(g) % 3 {
+            0 => EditOp::Insert {
+                index: start,
+                text: fragments(g, 16),
+            },
+            1 => EditOp::Remove { range },
+            _ => EditOp::Replace {
+                range,
+                text: fragments(g, 16),
+            },
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        match self.clone() {
+            EditOp::Insert { index, text } => Box::new(
+                (index, text)
+                    .shrink()
+                    .map(|(index, text)| EditOp::Insert { index, text }),
+            ),
+            EditOp::Remove { range } => Box::new(
+                (range.start, range.end - range.start)
+                    .shrink()
+                    .map(|(start, len)| EditOp::Remove {
+                        range: start..start + len,
+                    }),
+            ),
+            EditOp::Replace { range, text } => {
+                Box::new((range.start, range.end - range.start, text).shrink().map(
+                    |(start, len, text)| EditOp::Replace {
+                        range: start..start + len,
+                        text,
+                    },
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EditOp, RipString};
+    use ::quickcheck::{Arbitrary, Gen};
+
+    #[test]
+    fn test_arbitrary() {
+        let mut g = Gen::new(64);
+        for _ in 0..32 {
+            let rope = RipString::arbitrary(&mut g);
+            let (range, _) = EditOp::arbitrary(&mut g).resolve(rope.len());
+            assert!(range.start <= range.end && range.end <= rope.len());
+        }
+
+        let rope = RipString::from("a\r\nb");
+        assert!(rope.shrink().any(|smaller| smaller.is_empty()));
+        assert!(rope.shrink().all(|smaller| smaller != rope));
+
+        let op = EditOp::Remove { range: 4..6 };
+        assert!(op.shrink().all(|smaller| match smaller {
+            EditOp::Remove { range } => range.start <= 4 && range.len() <= 2,
+            _ => false,
+        }));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::RipString;
+
+fn is_whitespace(unit: &str) -> bool {
+    unit.chars().all(char::is_whitespace)
+}
+
+impl RipString {
+    /// Returns the index of the first unit at or after `index` for which `predicate`
+    /// returns false, or the length of the text.
+    pub fn scan_while<P: FnMut(&str) -> bool>(&self, index: usize, mut predicate: P) -> usize {
+        let len = self.len();
+        if index >= len {
+            return len;
+        }
+
+        let mut seg_index = self.find_segment(index);
+        while seg_index < self.nodes.len() {
+            let node = &self.nodes[seg_index];
+            let start = index.saturating_sub(node.index());
+            if start < node.len() {
+                if let Some(pos) = node
+                    .tp()
+                    .find_unit(start..node.len(), |unit| !predicate(unit))
+                {
+                    return node.index() + pos;
+                }
+            }
+            seg_index += 1;
+        }
+        len
+    }
+
+    /// Returns the smallest index such that `predicate` returns true for every unit
+    /// between it and `index`.
+    pub fn scan_back_while<P: FnMut(&str) -> bool>(&self, index: usize, mut predicate: P) -> usize {
+        let index = index.min(self.len());
+        if index == 0 {
+            return 0;
+        }
+
+        let mut seg_index = self.find_segment(index - 1);
+        loop {
+            let node = &self.nodes[seg_index];
+            let end = (index - node.index()).min(node.len());
+            if let Some(pos) = node.tp().rfind_unit(0..end, |unit| !predicate(unit)) {
+                return node.index() + pos + 1;
+            }
+            if seg_index == 0 {
+                return 0;
+            }
+            seg_index -= 1;
+        }
+    }
+
+    /// Returns the index of the first non-whitespace unit at or after `index`.
+    pub fn skip_whitespace_forward(&self, index: usize) -> usize {
+        self.scan_while(index, is_whitespace)
+    }
+
+    /// Returns the index after the last non-whitespace unit before `index`.
+    pub fn skip_whitespace_backward(&self, index: usize) -> usize {
+        self.scan_back_while(index, is_whitespace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::String;
+
+    #[test]
+    fn test_scan() {
+        let a = RipString::from("let x = \t\n  мир;  ");
+        assert_eq!(a.skip_whitespace_forward(3), 4);
+        assert_eq!(a.skip_whitespace_forward(7), 12);
+        assert_eq!(a.skip_whitespace_forward(16), 18);
+        assert_eq!(a.skip_whitespace_backward(12), 7);
+        assert_eq!(a.skip_whitespace_backward(100), 16);
+        assert_eq!(a.skip_whitespace_backward(0), 0);
+        assert_eq!(a.scan_while(12, |unit| unit != ";"), 15);
+        assert_eq!(a.scan_back_while(15, |unit| unit != " "), 12);
+    }
+
+    #[test]
+    fn test_scan_across_segments() {
+        let mut text = String::new();
+        for _ in 0..2000 {
+            text.push_str("a ");
+        }
+        text.push_str("мир🏡");
+        let a = RipString::from(text.as_str());
+        assert!(a.nodes.len() > 2);
+        assert_eq!(a.scan_while(0, |unit| unit != "🏡"), 4003);
+        assert_eq!(a.scan_back_while(4003, |unit| unit != "a"), 3999);
+        assert_eq!(a.scan_back_while(4004, |unit| unit != "b"), 0);
+    }
+}
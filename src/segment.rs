@@ -1,20 +1,28 @@
-use crate::splitter::{Splitter, MAX_BLOCK_SIZE, MIN_BLOCK_SIZE};
+use crate::segmenter::Segmenter;
+use crate::splitter::{SegmentKind, Splitter, MAX_BLOCK_SIZE, MIN_BLOCK_SIZE};
+use alloc::borrow::Cow;
 use alloc::collections::VecDeque;
 use alloc::fmt::{Debug, Display, Formatter};
 use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
-use core::mem;
+use core::iter::Enumerate;
 use core::ops::Range;
+use core::{mem, slice, str};
+use memchr::{memchr_iter, Memchr};
 
-#[derive(Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Segment {
     index: usize,
+    /// Byte offset of the segment start, maintained together with `index`.
+    byte_index: usize,
+    /// Cached `tp.byte_len()`.
+    byte_len: usize,
     tp: SegmentType,
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum SegmentType {
     Ascii(Vec<u8>),
     Utf8(Vec<char>),
@@ -26,6 +34,14 @@ impl SegmentType {
         self.len() == 0
     }
 
+    pub fn kind(&self) -> SegmentKind {
+        match self {
+            SegmentType::Ascii(_) => SegmentKind::Ascii,
+            SegmentType::Utf8(_) => SegmentKind::Utf8,
+            SegmentType::Unicode(_) => SegmentKind::Unicode,
+        }
+    }
+
     pub fn len(&self) -> usize {
         match &self {
             SegmentType::Ascii(val) => val.len(),
@@ -34,6 +50,16 @@ impl SegmentType {
         }
     }
 
+    /// Appends `seg_type` if either segment is smaller than the minimum block size and
+    /// both are of the same kind. Returns `seg_type` if it was not appended.
+    pub fn merge_small(&mut self, seg_type: SegmentType) -> Option<SegmentType> {
+        if self.len() < MIN_BLOCK_SIZE || seg_type.len() < MIN_BLOCK_SIZE {
+            self.try_merge(seg_type)
+        } else {
+            Some(seg_type)
+        }
+    }
+
     pub fn try_merge(&mut self, seg_type: SegmentType) -> Option<SegmentType> {
         if self.len() + seg_type.len() >= MAX_BLOCK_SIZE {
             return Some(seg_type);
@@ -67,6 +93,194 @@ impl SegmentType {
         }
     }
 
+    pub fn chars(&self, range: Range<usize>) -> Chars<'_> {
+        match self {
+            SegmentType::Ascii(val) => Chars::Ascii(val[range].iter()),
+            SegmentType::Utf8(val) => Chars::Utf8(val[range].iter()),
+            SegmentType::Unicode(val) => Chars::Unicode(val[range].iter(), "".chars()),
+        }
+    }
+
+    /// Returns the text of the unit at `index`, borrowed unless it is a `Utf8` char.
+    pub fn unit(&self, index: usize) -> Cow<'_, str> {
+        match self {
+            SegmentType::Ascii(val) => {
+                Cow::Borrowed(str::from_utf8(slice::from_ref(&val[index])).unwrap_or_default())
+            }
+            SegmentType::Utf8(val) => Cow::Owned(String::from(val[index])),
+            SegmentType::Unicode(val) => Cow::Borrowed(&val[index]),
+        }
+    }
+
+    /// Keeps only the units for which `f` returns true.
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut f: F) {
+        match self {
+            SegmentType::Ascii(val) => {
+                val.retain(|b| f(str::from_utf8(slice::from_ref(b)).unwrap_or_default()))
+            }
+            SegmentType::Utf8(val) => {
+                let mut buf = [0; 4];
+                val.retain(|ch| f(ch.encode_utf8(&mut buf)))
+            }
+            SegmentType::Unicode(val) => val.retain(|grapheme| f(grapheme)),
+        }
+    }
+
+    /// Calls `f` with the text of every unit in the range.
+    pub fn for_each_unit<F: FnMut(&str)>(&self, range: Range<usize>, mut f: F) {
+        match self {
+            SegmentType::Ascii(val) => {
+                for b in &val[range] {
+                    f(str::from_utf8(slice::from_ref(b)).unwrap_or_default());
+                }
+            }
+            SegmentType::Utf8(val) => {
+                let mut buf = [0; 4];
+                for ch in &val[range] {
+                    f(ch.encode_utf8(&mut buf));
+                }
+            }
+            SegmentType::Unicode(val) => {
+                for grapheme in &val[range] {
+                    f(grapheme);
+                }
+            }
+        }
+    }
+
+    /// Calls `f` with the text of the range in as few pieces as the segment allows
+    /// without allocating for every unit.
+    pub fn for_each_chunk<F: FnMut(&str)>(&self, range: Range<usize>, mut f: F) {
+        match self {
+            SegmentType::Ascii(val) => f(str::from_utf8(&val[range]).unwrap_or_default()),
+            SegmentType::Utf8(val) => f(&val[range].iter().collect::<String>()),
+            SegmentType::Unicode(val) => {
+                for grapheme in &val[range] {
+                    f(grapheme);
+                }
+            }
+        }
+    }
+
+    pub fn byte_len(&self) -> usize {
+        match self {
+            SegmentType::Ascii(val) => val.len(),
+            SegmentType::Utf8(val) => val.iter().map(|ch| ch.len_utf8()).sum(),
+            SegmentType::Unicode(val) => val.iter().map(String::len).sum(),
+        }
+    }
+
+    pub fn utf16_len(&self) -> usize {
+        match self {
+            SegmentType::Ascii(val) => val.len(),
+            SegmentType::Utf8(val) => val.iter().map(|ch| ch.len_utf16()).sum(),
+            SegmentType::Unicode(val) => val.iter().map(|g| utf16_len(g)).sum(),
+        }
+    }
+
+    pub fn char_count(&self) -> usize {
+        match self {
+            SegmentType::Ascii(val) => val.len(),
+            SegmentType::Utf8(val) => val.len(),
+            SegmentType::Unicode(val) => val.iter().map(|grapheme| grapheme.chars().count()).sum(),
+        }
+    }
+
+    /// Returns the position of the first unit in the range for which `f` returns true.
+    pub fn find_unit<F: FnMut(&str) -> bool>(
+        &self,
+        range: Range<usize>,
+        mut f: F,
+    ) -> Option<usize> {
+        let start = range.start;
+        match self {
+            SegmentType::Ascii(val) => val[range]
+                .iter()
+                .position(|b| f(str::from_utf8(slice::from_ref(b)).unwrap_or_default())),
+            SegmentType::Utf8(val) => {
+                let mut buf = [0; 4];
+                val[range].iter().position(|ch| f(ch.encode_utf8(&mut buf)))
+            }
+            SegmentType::Unicode(val) => val[range].iter().position(|grapheme| f(grapheme)),
+        }
+        .map(|pos| start + pos)
+    }
+
+    /// Returns the position of the last unit in the range for which `f` returns true.
+    pub fn rfind_unit<F: FnMut(&str) -> bool>(
+        &self,
+        range: Range<usize>,
+        mut f: F,
+    ) -> Option<usize> {
+        let start = range.start;
+        match self {
+            SegmentType::Ascii(val) => val[range]
+                .iter()
+                .rposition(|b| f(str::from_utf8(slice::from_ref(b)).unwrap_or_default())),
+            SegmentType::Utf8(val) => {
+                let mut buf = [0; 4];
+                val[range]
+                    .iter()
+                    .rposition(|ch| f(ch.encode_utf8(&mut buf)))
+            }
+            SegmentType::Unicode(val) => val[range].iter().rposition(|grapheme| f(grapheme)),
+        }
+        .map(|pos| start + pos)
+    }
+
+    /// Returns the positions of `\n` units.
+    pub fn newlines(&self) -> Newlines<'_> {
+        match self {
+            SegmentType::Ascii(val) => Newlines::Ascii(memchr_iter(b'\n', val)),
+            SegmentType::Utf8(val) => Newlines::Utf8(val.iter().enumerate()),
+            // A line feed is never a part of a longer grapheme cluster.
+            SegmentType::Unicode(_) => Newlines::Unicode,
+        }
+    }
+
+    /// Returns true if the unit at `index` is the given single char.
+    pub fn is_char(&self, index: usize, ch: char) -> bool {
+        match self {
+            SegmentType::Ascii(val) => val.get(index).is_some_and(|b| *b as char == ch),
+            SegmentType::Utf8(val) => val.get(index) == Some(&ch),
+            SegmentType::Unicode(_) => false,
+        }
+    }
+
+    /// Rewrites line terminators to `\r\n` or `\n`. `prev_cr` tells whether the unit
+    /// preceding the segment is `\r`.
+    pub fn convert_line_endings(&self, crlf: bool, prev_cr: bool) -> SegmentType {
+        fn convert<T: Copy + PartialEq>(
+            val: &[T],
+            cr: T,
+            lf: T,
+            crlf: bool,
+            prev_cr: bool,
+        ) -> Vec<T> {
+            let mut out = Vec::with_capacity(val.len());
+            for (i, unit) in val.iter().enumerate() {
+                if *unit == lf {
+                    let after_cr = if i == 0 { prev_cr } else { val[i - 1] == cr };
+                    if crlf && !after_cr {
+                        out.push(cr);
+                    } else if !crlf && after_cr && i > 0 {
+                        out.pop();
+                    }
+                }
+                out.push(*unit);
+            }
+            out
+        }
+
+        match self {
+            SegmentType::Ascii(val) => {
+                SegmentType::Ascii(convert(val, b'\r', b'\n', crlf, prev_cr))
+            }
+            SegmentType::Utf8(val) => SegmentType::Utf8(convert(val, '\r', '\n', crlf, prev_cr)),
+            SegmentType::Unicode(val) => SegmentType::Unicode(val.clone()),
+        }
+    }
+
     pub fn split(&mut self, at: usize) -> SegmentType {
         match self {
             SegmentType::Ascii(val) => SegmentType::Ascii(val.split_off(at)),
@@ -76,37 +290,51 @@ impl SegmentType {
     }
 }
 
+pub fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
 impl Segment {
     pub fn new(index: usize, tp: SegmentType) -> Segment {
-        Segment { index, tp }
+        Segment {
+            index,
+            byte_index: 0,
+            byte_len: tp.byte_len(),
+            tp,
+        }
     }
 
     pub fn try_merge(&mut self, new_segments: &mut VecDeque<SegmentType>) {
         if let Some(first) = new_segments.pop_front() {
-            if let Some(first) = self.tp.try_merge(first) {
+            if let Some(first) = self.modify(|tp| tp.try_merge(first)) {
                 new_segments.insert(0, first);
             }
         }
     }
 
-    pub fn insert(&mut self, index: usize, text: &str) -> Option<VecDeque<Segment>> {
+    pub fn insert(
+        &mut self,
+        index: usize,
+        text: &str,
+        segmenter: &dyn Segmenter,
+    ) -> Option<VecDeque<Segment>> {
         let index = index - self.index;
-        let mut new_segments = Splitter::new(text).collect::<VecDeque<_>>();
+        let mut new_segments = Splitter::new(text, segmenter).collect::<VecDeque<_>>();
 
         if self.len() == 0 {
             if let Some(val) = new_segments.pop_front() {
-                self.tp = val;
+                self.modify(|tp| *tp = val);
             }
-        } else if index == self.len() - 1 {
+        } else if index == self.len() {
             self.try_merge(&mut new_segments);
         } else if index == 0 {
             if let Some(mut first) = new_segments.pop_front() {
-                mem::swap(&mut self.tp, &mut first);
+                self.modify(|tp| mem::swap(tp, &mut first));
                 new_segments.push_back(first);
                 self.try_merge(&mut new_segments);
             }
         } else {
-            new_segments.push_back(self.tp.split(index));
+            new_segments.push_back(self.modify(|tp| tp.split(index)));
             self.try_merge(&mut new_segments);
         }
 
@@ -132,37 +360,37 @@ impl Segment {
         }
 
         if end >= self.len() {
-            self.tp.split(start);
+            self.modify(|tp| tp.split(start));
             None
         } else {
-            let mut last = self.tp.split(start);
-            let last = last.split(end - start);
-            if last.len() < MIN_BLOCK_SIZE || self.tp.len() < MIN_BLOCK_SIZE {
-                if let Some(last) = self.tp.try_merge(last) {
-                    if last.is_empty() {
-                        None
-                    } else {
-                        Some(Segment::new(0, last))
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+            self.modify(|tp| {
+                let mut last = tp.split(start);
+                let last = last.split(end - start);
+                tp.merge_small(last)
+            })
+            .filter(|last| !last.is_empty())
+            .map(|last| Segment::new(0, last))
         }
     }
 
-    pub fn replace(&mut self, range: Range<usize>, text: &str) -> Option<VecDeque<Segment>> {
+    pub fn replace(
+        &mut self,
+        range: Range<usize>,
+        text: &str,
+        segmenter: &dyn Segmenter,
+    ) -> Option<VecDeque<Segment>> {
         let start = range.start - self.index;
         let end = range.end - self.index;
-        let mut new_segments = Splitter::new(text).collect::<VecDeque<_>>();
+        let mut new_segments = Splitter::new(text, segmenter).collect::<VecDeque<_>>();
         if end > self.len() {
-            self.tp.split(start);
+            self.modify(|tp| tp.split(start));
             self.try_merge(&mut new_segments);
         } else {
-            let end = self.tp.split(end);
-            self.tp.split(start);
+            let end = self.modify(|tp| {
+                let end = tp.split(end);
+                tp.split(start);
+                end
+            });
             self.try_merge(&mut new_segments);
 
             if !end.is_empty() {
@@ -187,6 +415,37 @@ impl Segment {
         self.tp.len()
     }
 
+    pub fn chars(&self) -> Chars<'_> {
+        self.tp.chars(0..self.len())
+    }
+
+    pub fn for_each_unit<F: FnMut(&str)>(&self, range: Range<usize>, f: F) {
+        self.tp.for_each_unit(range, f)
+    }
+
+    pub fn for_each_chunk<F: FnMut(&str)>(&self, range: Range<usize>, f: F) {
+        self.tp.for_each_chunk(range, f)
+    }
+
+    pub fn newlines(&self) -> Newlines<'_> {
+        self.tp.newlines()
+    }
+
+    pub fn tp(&self) -> &SegmentType {
+        &self.tp
+    }
+
+    /// Changes the content with `f` and refreshes the cached byte length.
+    pub fn modify<R, F: FnOnce(&mut SegmentType) -> R>(&mut self, f: F) -> R {
+        let result = f(&mut self.tp);
+        self.byte_len = self.tp.byte_len();
+        result
+    }
+
+    pub fn into_tp(self) -> SegmentType {
+        self.tp
+    }
+
     pub fn set_index(&mut self, index: usize) {
         self.index = index;
     }
@@ -195,6 +454,18 @@ impl Segment {
         self.index
     }
 
+    pub fn set_byte_index(&mut self, byte_index: usize) {
+        self.byte_index = byte_index;
+    }
+
+    pub fn byte_index(&self) -> usize {
+        self.byte_index
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.byte_len
+    }
+
     pub fn contains(&self, index: usize) -> bool {
         self.ord(index) == Ordering::Equal
     }
@@ -214,6 +485,47 @@ impl Segment {
     }
 }
 
+pub enum Chars<'a> {
+    Ascii(slice::Iter<'a, u8>),
+    Utf8(slice::Iter<'a, char>),
+    Unicode(slice::Iter<'a, String>, str::Chars<'a>),
+}
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Chars::Ascii(iter) => iter.next().map(|b| *b as char),
+            Chars::Utf8(iter) => iter.next().copied(),
+            Chars::Unicode(iter, current) => loop {
+                if let Some(ch) = current.next() {
+                    return Some(ch);
+                }
+                *current = iter.next()?.chars();
+            },
+        }
+    }
+}
+
+pub enum Newlines<'a> {
+    Ascii(Memchr<'a>),
+    Utf8(Enumerate<slice::Iter<'a, char>>),
+    Unicode,
+}
+
+impl<'a> Iterator for Newlines<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Newlines::Ascii(iter) => iter.next(),
+            Newlines::Utf8(iter) => iter.find(|(_, ch)| **ch == '\n').map(|(i, _)| i),
+            Newlines::Unicode => None,
+        }
+    }
+}
+
 impl Debug for SegmentType {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -258,43 +570,32 @@ impl Display for Segment {
 
 impl From<Vec<u8>> for Segment {
     fn from(val: Vec<u8>) -> Self {
-        Segment {
-            index: 0,
-            tp: SegmentType::Ascii(val),
-        }
+        Segment::new(0, SegmentType::Ascii(val))
     }
 }
 
 impl From<Vec<char>> for Segment {
     fn from(val: Vec<char>) -> Self {
-        Segment {
-            index: 0,
-            tp: SegmentType::Utf8(val),
-        }
+        Segment::new(0, SegmentType::Utf8(val))
     }
 }
 
 impl From<Vec<String>> for Segment {
     fn from(val: Vec<String>) -> Self {
-        Segment {
-            index: 0,
-            tp: SegmentType::Unicode(val),
-        }
+        Segment::new(0, SegmentType::Unicode(val))
     }
 }
 
 impl Default for Segment {
     fn default() -> Self {
-        Segment {
-            index: 0,
-            tp: SegmentType::Ascii(vec![]),
-        }
+        Segment::new(0, SegmentType::Ascii(vec![]))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::segment::{Segment, SegmentType};
+    use crate::segmenter::DEFAULT;
     use alloc::format;
     use alloc::string::ToString;
     use core::cmp::Ordering;
@@ -315,28 +616,28 @@ mod tests {
     #[test]
     fn test_insert() {
         let mut seg = Segment::new(0, SegmentType::Ascii("Hello world".as_bytes().to_vec()));
-        assert!(seg.insert(10, ". Hi, bro.").is_none());
+        assert!(seg.insert(11, ". Hi, bro.", DEFAULT).is_none());
         assert_eq!(seg.to_string(), "Hello world. Hi, bro.".to_string());
 
-        assert!(seg.insert(0, "Hi, bro.").is_none());
+        assert!(seg.insert(0, "Hi, bro.", DEFAULT).is_none());
         assert_eq!(seg.to_string(), "Hi, bro.Hello world. Hi, bro.".to_string());
 
-        let last = seg.insert(8, " ").unwrap().pop_front().unwrap();
+        let last = seg.insert(8, " ", DEFAULT).unwrap().pop_front().unwrap();
         assert_eq!(seg.to_string(), "Hi, bro. ".to_string());
         assert_eq!(last.to_string(), "Hello world. Hi, bro.".to_string());
 
-        let mut last = seg.insert(2, "🏡 ").unwrap();
+        let mut last = seg.insert(2, "🏡 ", DEFAULT).unwrap();
         assert_eq!(seg.to_string(), "Hi".to_string());
         assert_eq!(last.pop_front().unwrap().to_string(), "🏡".to_string());
         assert_eq!(last.pop_front().unwrap().to_string(), " ".to_string());
         assert_eq!(last.pop_front().unwrap().to_string(), ", bro. ".to_string());
 
         let mut seg = Segment::new(0, SegmentType::Ascii("".as_bytes().to_vec()));
-        seg.insert(0, "H");
-        seg.insert(1, "e");
-        seg.insert(2, "l");
-        seg.insert(3, "l");
-        seg.insert(4, "o");
+        seg.insert(0, "H", DEFAULT);
+        seg.insert(1, "e", DEFAULT);
+        seg.insert(2, "l", DEFAULT);
+        seg.insert(3, "l", DEFAULT);
+        seg.insert(4, "o", DEFAULT);
         assert_eq!(seg.to_string(), "Hello".to_string());
     }
 
@@ -357,33 +658,39 @@ mod tests {
         let mut seg = Segment::new(0, SegmentType::Ascii("Hello world".as_bytes().to_vec()));
         assert!(seg.cut(5..6).is_none());
         assert_eq!(seg.to_string(), "Helloworld");
+
+        let text = format!("{}{}", "a".repeat(600), "b".repeat(600));
+        let mut seg = Segment::new(0, SegmentType::Ascii(text.into_bytes()));
+        let rest = seg.cut(550..560).unwrap();
+        assert_eq!(seg.len(), 550);
+        assert_eq!(
+            rest.to_string(),
+            format!("{}{}", "a".repeat(40), "b".repeat(600))
+        );
     }
 
     #[test]
     fn test_replace() {
         let mut seg = Segment::new(0, SegmentType::Ascii("Hello world".as_bytes().to_vec()));
-        assert!(seg.replace(6..11, "Json").is_none());
+        assert!(seg.replace(6..11, "Json", DEFAULT).is_none());
         assert_eq!(seg.to_string(), "Hello Json");
-        let mut last = seg.replace(7..7, "ack").unwrap();
+        let mut last = seg.replace(7..7, "ack", DEFAULT).unwrap();
         assert_eq!(seg.to_string(), "Hello Jack");
         assert_eq!(last.pop_front().unwrap().to_string(), "son".to_string());
 
         let mut seg = Segment::new(0, SegmentType::Ascii("Hello world".as_bytes().to_vec()));
-        assert!(seg.replace(6..20, "Json").is_none());
+        assert!(seg.replace(6..20, "Json", DEFAULT).is_none());
         assert_eq!(seg.to_string(), "Hello Json");
 
         let mut seg = Segment::new(0, SegmentType::Ascii("Hello world".as_bytes().to_vec()));
-        assert!(seg.replace(5..20, " ").is_none());
+        assert!(seg.replace(5..20, " ", DEFAULT).is_none());
         assert_eq!(seg.to_string(), "Hello ");
     }
 
     #[test]
     fn replace_small() {
         let mut seg = Segment::new(0, SegmentType::Ascii("hello world".as_bytes().to_vec()));
-        let mut new_seg = seg.replace(1..9, "era").unwrap();
-        assert_eq!(
-            "herald",
-            format!("{}{}", seg.to_string(), new_seg.pop_front().unwrap())
-        );
+        let mut new_seg = seg.replace(1..9, "era", DEFAULT).unwrap();
+        assert_eq!("herald", format!("{}{}", seg, new_seg.pop_front().unwrap()));
     }
 }
@@ -0,0 +1,130 @@
+use alloc::fmt::Debug;
+#[cfg(feature = "seshat")]
+use seshat::unicode::Segmentation;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Grapheme cluster breaking used to split text into indexable units.
+///
+/// The default implementation is backed by seshat, or by unicode-segmentation without
+/// the `seshat` feature, which needs `std`. Implement this trait to plug in
+/// another Unicode library, pin a Unicode version or use domain-specific clusters.
+///
+/// The segmenter defines what a single index unit is, so indices stored by the
+/// application are only stable while the segmenter is. Seshat is pinned to an exact
+/// version for this reason.
+pub trait Segmenter: Debug + Sync {
+    /// Returns the length in bytes of the grapheme cluster at the start of `text`.
+    fn grapheme_len(&self, text: &str) -> usize;
+}
+
+#[cfg(feature = "seshat")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Seshat;
+
+#[cfg(feature = "seshat")]
+impl Segmenter for Seshat {
+    fn grapheme_len(&self, text: &str) -> usize {
+        text.break_graphemes().next().map_or(0, str::len)
+    }
+}
+
+/// Extended grapheme clusters of unicode-segmentation, which also builds without `std`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Extended;
+
+impl Segmenter for Extended {
+    fn grapheme_len(&self, text: &str) -> usize {
+        text.graphemes(true).next().map_or(0, str::len)
+    }
+}
+
+/// Segmenter of strings created without choosing one.
+#[cfg(feature = "seshat")]
+pub(crate) const DEFAULT: &dyn Segmenter = &Seshat;
+#[cfg(not(feature = "seshat"))]
+pub(crate) const DEFAULT: &dyn Segmenter = &Extended;
+
+/// Legacy grapheme clusters, which unlike extended clusters do not attach spacing
+/// marks and prepended characters.
+#[cfg(feature = "legacy-graphemes")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Legacy;
+
+#[cfg(feature = "legacy-graphemes")]
+impl Segmenter for Legacy {
+    fn grapheme_len(&self, text: &str) -> usize {
+        text.graphemes(false).next().map_or(0, str::len)
+    }
+}
+
+pub struct Graphemes<'a> {
+    text: &'a str,
+    segmenter: &'a dyn Segmenter,
+}
+
+impl<'a> Graphemes<'a> {
+    pub fn new(text: &'a str, segmenter: &'a dyn Segmenter) -> Graphemes<'a> {
+        Graphemes { text, segmenter }
+    }
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.text.is_empty() {
+            return None;
+        }
+
+        let mut len = self.segmenter.grapheme_len(self.text);
+        // Never trust a segmenter to stall the iteration or to split a code point.
+        if len == 0 || len > self.text.len() {
+            len = self.text.chars().next().map_or(0, char::len_utf8);
+        }
+        while !self.text.is_char_boundary(len) {
+            len += 1;
+        }
+
+        let (grapheme, rest) = self.text.split_at(len);
+        self.text = rest;
+        Some(grapheme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segmenter::{Extended, Graphemes, Segmenter, DEFAULT};
+    use alloc::vec::Vec;
+
+    #[derive(Debug)]
+    struct Chars;
+
+    impl Segmenter for Chars {
+        fn grapheme_len(&self, text: &str) -> usize {
+            text.chars().next().map_or(0, char::len_utf8)
+        }
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let text = "a\r\n👨‍👩‍👧‍👦é";
+        assert_eq!(
+            Graphemes::new(text, DEFAULT).collect::<Vec<_>>(),
+            ["a", "\r\n", "👨‍👩‍👧‍👦", "é"]
+        );
+        assert!(Graphemes::new(text, &Extended).eq(Graphemes::new(text, DEFAULT)));
+        assert_eq!(Graphemes::new(text, &Chars).count(), text.chars().count());
+    }
+
+    #[cfg(feature = "legacy-graphemes")]
+    #[test]
+    fn test_legacy() {
+        use crate::segmenter::Legacy;
+
+        // A consonant followed by a spacing vowel sign.
+        let text = "\u{915}\u{93f}";
+        assert_eq!(Graphemes::new(text, DEFAULT).count(), 1);
+        assert_eq!(Graphemes::new(text, &Extended).count(), 1);
+        assert_eq!(Graphemes::new(text, &Legacy).count(), 2);
+    }
+}
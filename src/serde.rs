@@ -0,0 +1,64 @@
+//! Serde support. A `RipString` is serialized as a plain string, written segment by
+//! segment when the serializer supports it.
+
+use crate::RipString;
+use ::serde::de::{Deserialize, Deserializer, Error, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+use alloc::string::String;
+use core::fmt::{self, Formatter};
+use core::str;
+
+impl Serialize for RipString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct RipStringVisitor;
+
+impl<'de> Visitor<'de> for RipStringVisitor {
+    type Value = RipString;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: Error>(self, val: &str) -> Result<RipString, E> {
+        Ok(RipString::from(val))
+    }
+
+    fn visit_string<E: Error>(self, val: String) -> Result<RipString, E> {
+        Ok(RipString::from(val))
+    }
+
+    fn visit_bytes<E: Error>(self, val: &[u8]) -> Result<RipString, E> {
+        str::from_utf8(val)
+            .map(RipString::from)
+            .map_err(|_| E::invalid_value(::serde::de::Unexpected::Bytes(val), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for RipString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RipString, D::Error> {
+        deserializer.deserialize_string(RipStringVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+    #[test]
+    fn test_serde() {
+        let a = RipString::from("Hello, мир 🏡 e\u{301}\r\n");
+        assert_tokens(&a, &[Token::Str("Hello, мир 🏡 e\u{301}\r\n")]);
+        assert_de_tokens(&a, &[Token::String("Hello, мир 🏡 e\u{301}\r\n")]);
+        assert_de_tokens(&a, &[Token::Bytes("Hello, мир 🏡 e\u{301}\r\n".as_bytes())]);
+        assert_de_tokens(&RipString::new(), &[Token::BorrowedStr("")]);
+        assert_de_tokens_error::<RipString>(
+            &[Token::Bytes(&[0x61, 0xff])],
+            "invalid value: byte array, expected a string",
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{Chars, Pattern, RipString};
+use alloc::borrow::Cow;
+use alloc::fmt::{Display, Formatter};
+use alloc::vec::Vec;
+use core::ops::{Range, RangeBounds};
+
+/// A borrowed view of a range of a `RipString`. Indices are relative to the start of
+/// the view.
+#[derive(Debug, Clone, Copy)]
+pub struct RipSlice<'a> {
+    rip: &'a RipString,
+    start: usize,
+    end: usize,
+}
+
+impl RipString {
+    /// Returns a view of the range. Panics if the range is inverted or out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> RipSlice<'_> {
+        let range = self.resolve_range(range);
+        self.check_or_panic(&range);
+        RipSlice {
+            rip: self,
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl RipString {
+    /// Returns the text of the unit at `index`, usually a whole grapheme cluster.
+    pub fn get(&self, index: usize) -> Option<Cow<'_, str>> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut seg_index = self.find_segment(index);
+        // A segment also matches the index right after its end.
+        if index >= self.nodes[seg_index].index() + self.nodes[seg_index].len() {
+            seg_index += 1;
+        }
+        let node = &self.nodes[seg_index];
+        Some(node.tp().unit(index - node.index()))
+    }
+
+    /// Returns a view of the range, or `None` if it is inverted or out of bounds.
+    pub fn range(&self, range: Range<usize>) -> Option<RipSlice<'_>> {
+        self.check_range(&range).ok().map(|_| RipSlice {
+            rip: self,
+            start: range.start,
+            end: range.end,
+        })
+    }
+}
+
+impl<'a> RipSlice<'a> {
+    /// Returns the length in units.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the range of the view in the string it borrows from.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns a view of a range of this view. Panics if the range is inverted or out of
+    /// bounds.
+    pub fn slice(&self, range: Range<usize>) -> RipSlice<'a> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range {:?} is out of bounds of a slice of length {}",
+            range,
+            self.len()
+        );
+        RipSlice {
+            rip: self.rip,
+            start: self.start + range.start,
+            end: self.start + range.end,
+        }
+    }
+
+    pub fn chars(&self) -> Chars<'a> {
+        let seg_index = self.rip.find_segment(self.start);
+        Chars::in_range(&self.rip.nodes[seg_index..], self.range())
+    }
+
+    /// Calls `f` with consecutive pieces of the text of the view.
+    pub fn for_each_chunk<F: FnMut(&str)>(&self, f: F) {
+        self.rip.for_each_chunk(self.range(), f)
+    }
+
+    /// Returns the range of the first match of the pattern within the view. The view is
+    /// searched segment by segment and the search stops at the first match.
+    pub fn find<P: Pattern>(&self, pattern: P) -> Option<Range<usize>> {
+        self.rip
+            .match_ranges(self.range(), pattern, 1)
+            .pop()
+            .map(|range| self.relative(range))
+    }
+
+    /// Returns the ranges of all non-overlapping matches of the pattern within the view.
+    /// Matches may cross segment boundaries but not the ends of the view.
+    pub fn find_all<P: Pattern>(&self, pattern: P) -> Vec<Range<usize>> {
+        self.rip
+            .match_ranges(self.range(), pattern, usize::MAX)
+            .into_iter()
+            .map(|range| self.relative(range))
+            .collect()
+    }
+
+    fn relative(&self, range: Range<usize>) -> Range<usize> {
+        range.start - self.start..range.end - self.start
+    }
+}
+
+impl Display for RipSlice<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut result = Ok(());
+        self.for_each_chunk(|chunk| {
+            if result.is_ok() {
+                result = f.write_str(chunk);
+            }
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::borrow::Cow;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_slice() {
+        let text = "ok мир e\u{301}🏡\n".repeat(200);
+        let a = RipString::from(text.as_str());
+        let slice = a.slice(1000..1600);
+        assert_eq!(slice.len(), 600);
+        assert_eq!(slice.to_string(), a.substring(1000..1600));
+        assert_eq!(slice.chars().collect::<String>(), slice.to_string());
+        assert_eq!(slice.range(), 1000..1600);
+
+        let sub = slice.slice(7..13);
+        assert_eq!(sub.to_string(), "e\u{301}🏡\nok ");
+        assert_eq!(sub.find("ok"), Some(3..5));
+        assert_eq!(sub.find_all(char::is_alphabetic), [3..4, 4..5]);
+        assert_eq!(slice.find_all("мир").len(), 60);
+        assert!(sub.slice(2..2).is_empty());
+
+        assert!(a.nodes.len() > 1);
+        assert_eq!(a.slice(..).find_all("🏡\nok").len(), 199);
+        assert_eq!(a.slice(1..11).find("ok"), None);
+        assert_eq!(a.slice(1..12).find("ok"), Some(9..11));
+        let tail = a.slice(a.len() - 25..);
+        assert_eq!(tail.find_all("🏡\n"), [3..5, 13..15, 23..25]);
+        assert_eq!(a.slice(0..0).chars().next(), None);
+    }
+
+    #[test]
+    fn test_get() {
+        let a = RipString::from("aб e\u{301}🏡\r\n");
+        let units = (0..a.len()).map(|i| a.get(i).unwrap()).collect::<Vec<_>>();
+        assert_eq!(units, ["a", "б", " ", "e\u{301}", "🏡", "\r", "\n"]);
+        assert!(matches!(a.get(0), Some(Cow::Borrowed("a"))));
+        assert_eq!(a.get(7), None);
+
+        assert_eq!(a.range(1..5).unwrap().to_string(), "б e\u{301}🏡");
+        assert!(a.range(5..8).is_none());
+        assert!(a.range(7..7).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+//! Binary snapshots keeping the segments of a string, so loading does not run the
+//! splitter.
+//!
+//! Layout: the magic `RIPS` and a version byte, then the segment count and the last
+//! edited segment, then for every segment its index, kind byte, unit count and text.
+//! ASCII text is stored as is, `Utf8` text is prefixed with its byte length and every
+//! `Unicode` cluster with its own. Numbers are LEB128 varints.
+
+use crate::segment::{Segment, SegmentType};
+use crate::splitter::MAX_BLOCK_SIZE;
+use crate::{RipError, RipString};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str;
+
+const MAGIC: &[u8] = b"RIPS";
+const VERSION: u8 = 1;
+
+const ASCII: u8 = 0;
+const UTF8: u8 = 1;
+const UNICODE: u8 = 2;
+
+fn push_varint(out: &mut Vec<u8>, mut val: usize) {
+    while val >= 0x80 {
+        out.push(val as u8 | 0x80);
+        val >>= 7;
+    }
+    out.push(val as u8);
+}
+
+/// Cursor over snapshot data. Errors carry the offset of the offending byte.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self) -> RipError {
+        RipError::InvalidSnapshot(self.pos)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], RipError> {
+        match self.data.get(self.pos..self.pos.saturating_add(len)) {
+            Some(bytes) => {
+                self.pos += len;
+                Ok(bytes)
+            }
+            None => Err(RipError::InvalidSnapshot(self.data.len())),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, RipError> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn varint(&mut self) -> Result<usize, RipError> {
+        let start = self.pos;
+        let mut val = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            let bits = (byte & 0x7f) as usize;
+            if shift >= usize::BITS || (bits << shift) >> shift != bits {
+                return Err(RipError::InvalidSnapshot(start));
+            }
+            val |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(val);
+            }
+            shift += 7;
+        }
+    }
+
+    fn str(&mut self, len: usize) -> Result<&'a str, RipError> {
+        let start = self.pos;
+        let bytes = self.bytes(len)?;
+        str::from_utf8(bytes).map_err(|err| RipError::InvalidSnapshot(start + err.valid_up_to()))
+    }
+
+    fn segment_type(&mut self, kind: u8, units: usize) -> Result<SegmentType, RipError> {
+        let start = self.pos;
+        let tp = match kind {
+            ASCII => {
+                let bytes = self.bytes(units)?;
+                if let Some(pos) = bytes.iter().position(|b| !b.is_ascii()) {
+                    return Err(RipError::InvalidSnapshot(start + pos));
+                }
+                SegmentType::Ascii(bytes.to_vec())
+            }
+            UTF8 => {
+                let len = self.varint()?;
+                let chars = self.str(len)?.chars().collect::<Vec<_>>();
+                if chars.len() != units {
+                    return Err(RipError::InvalidSnapshot(start));
+                }
+                SegmentType::Utf8(chars)
+            }
+            UNICODE => {
+                let mut graphemes = Vec::with_capacity(units);
+                for _ in 0..units {
+                    let len = self.varint()?;
+                    if len == 0 {
+                        return Err(self.error());
+                    }
+                    graphemes.push(String::from(self.str(len)?));
+                }
+                SegmentType::Unicode(graphemes)
+            }
+            _ => return Err(RipError::InvalidSnapshot(start - 1)),
+        };
+        Ok(tp)
+    }
+}
+
+impl RipString {
+    /// Encodes the text together with its segments, see `from_snapshot`.
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAGIC.len() + 1 + self.len_bytes() + 8 * self.nodes.len());
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        push_varint(&mut out, self.nodes.len());
+        push_varint(&mut out, self.last_edit);
+        for node in &self.nodes {
+            push_varint(&mut out, node.index());
+            match node.tp() {
+                SegmentType::Ascii(val) => {
+                    out.push(ASCII);
+                    push_varint(&mut out, val.len());
+                    out.extend_from_slice(val);
+                }
+                SegmentType::Utf8(val) => {
+                    out.push(UTF8);
+                    push_varint(&mut out, val.len());
+                    push_varint(&mut out, val.iter().map(|ch| ch.len_utf8()).sum());
+                    let mut buf = [0; 4];
+                    for ch in val {
+                        out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                    }
+                }
+                SegmentType::Unicode(val) => {
+                    out.push(UNICODE);
+                    push_varint(&mut out, val.len());
+                    for grapheme in val {
+                        push_varint(&mut out, grapheme.len());
+                        out.extend_from_slice(grapheme.as_bytes());
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Restores a string from `to_snapshot` output with the segments it had, without
+    /// running the splitter. Fails with the offset of the first malformed byte, including
+    /// segments that break the invariants checked by `validate`.
+    pub fn from_snapshot(data: &[u8]) -> Result<RipString, RipError> {
+        let mut reader = Reader { data, pos: 0 };
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(RipError::InvalidSnapshot(0));
+        }
+        if reader.byte()? != VERSION {
+            return Err(RipError::InvalidSnapshot(MAGIC.len()));
+        }
+
+        let count = reader.varint()?;
+        let last_edit_pos = reader.pos;
+        let last_edit = reader.varint()?;
+        let mut nodes = Vec::with_capacity(count.min(data.len()));
+        let mut expected = 0;
+        for _ in 0..count {
+            let start = reader.pos;
+            let index = reader.varint()?;
+            let kind = reader.byte()?;
+            let units = reader.varint()?;
+            if index != expected || units > MAX_BLOCK_SIZE || (units == 0 && count > 1) {
+                return Err(RipError::InvalidSnapshot(start));
+            }
+            nodes.push(Segment::new(index, reader.segment_type(kind, units)?));
+            expected += units;
+        }
+        if nodes.is_empty() || last_edit >= nodes.len() {
+            return Err(RipError::InvalidSnapshot(last_edit_pos));
+        }
+        if reader.pos != data.len() {
+            return Err(reader.error());
+        }
+
+        let mut rip = RipString::new();
+        rip.nodes = nodes;
+        rip.fix_index_from(0);
+        rip.last_edit = last_edit;
+        Ok(rip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipError, RipString};
+
+    #[test]
+    fn test_snapshot() {
+        let text = "line мир 🏡 e\u{301}\r\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.edit(1000..1010, "edited");
+        a.insert(5, "!");
+        let snapshot = a.to_snapshot();
+        let b = RipString::from_snapshot(&snapshot).unwrap();
+        assert_eq!(b, a);
+        assert_eq!(b.dump_structure(), a.dump_structure());
+        assert_eq!(b.validate(), Ok(()));
+
+        let text = "0123456789\n".repeat(50);
+        let snapshot = RipString::from(text.as_str()).to_snapshot();
+        assert_eq!(snapshot.len(), text.len() + 11);
+
+        let empty = RipString::new().to_snapshot();
+        assert!(RipString::from_snapshot(&empty).unwrap().is_empty());
+
+        let a = RipString::from("ab мир");
+        let mut snapshot = a.to_snapshot();
+        assert_eq!(
+            RipString::from_snapshot(&snapshot[..snapshot.len() - 1]),
+            Err(RipError::InvalidSnapshot(snapshot.len() - 1))
+        );
+        snapshot[4] = 9;
+        assert_eq!(
+            RipString::from_snapshot(&snapshot),
+            Err(RipError::InvalidSnapshot(4))
+        );
+        assert_eq!(
+            RipString::from_snapshot(b"RIPS\x01\x01\x00\x00\x00\x02a\xff"),
+            Err(RipError::InvalidSnapshot(11))
+        );
+        assert_eq!(
+            RipString::from_snapshot(b"RIPS\x01\x01\x01\x00\x00\x00"),
+            Err(RipError::InvalidSnapshot(6))
+        );
+    }
+}
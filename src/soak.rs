@@ -0,0 +1,95 @@
+//! Seeded random edit sessions checked against a `String` oracle.
+
+use crate::edit_op::FRAGMENTS;
+use crate::rng::SplitMix;
+use crate::{EditOp, RipString};
+use alloc::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+
+/// The first operation after which the rope no longer matches the oracle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Zero-based number of the operation.
+    pub step: usize,
+    pub op: EditOp,
+    /// Text before the operation.
+    pub before: String,
+    /// Text of the oracle after the operation.
+    pub expected: String,
+    /// Text of the rope after the operation.
+    pub actual: String,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Step {}: {:?} on {:?} gave {:?} instead of {:?}",
+            self.step, self.op, self.before, self.actual, self.expected
+        )
+    }
+}
+
+/// Runs `ops` random inserts, removals and replacements against a rope and a `String`.
+/// The same seed always replays the same session.
+pub fn run(seed: u64, ops: usize) -> Result<(), Divergence> {
+    let mut rng = SplitMix(seed);
+    let mut rope = RipString::from(fragments(&mut rng, 64).as_str());
+    let mut oracle = rope.to_string();
+
+    for step in 0..ops {
+        let len = rope.len();
+        let start = rng.below(len + 1);
+        let range = start..start + rng.below(len - start + 1);
+        let op = match rng.below(3) {
+            0 => EditOp::Insert {
+                index: start,
+                text: fragments(&mut rng, 8),
+            },
+            1 => EditOp::Remove { range },
+            _ => EditOp::Replace {
+                range,
+                text: fragments(&mut rng, 8),
+            },
+        };
+
+        let (range, text) = op.resolve(len);
+        let bytes = rope.index_to_byte(range.start)..rope.index_to_byte(range.end);
+        let before = oracle.clone();
+        oracle.replace_range(bytes, text);
+        op.apply(&mut rope);
+
+        let actual = rope.to_string();
+        if actual != oracle {
+            return Err(Divergence {
+                step,
+                op,
+                before,
+                expected: oracle,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Text of up to `max` fragments.
+fn fragments(rng: &mut SplitMix, max: usize) -> String {
+    (0..1 + rng.below(max))
+        .map(|_| FRAGMENTS[rng.below(FRAGMENTS.len())])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soak::run;
+
+    #[test]
+    fn test_soak() {
+        for seed in 0..50 {
+            if let Err(divergence) = run(seed, 300) {
+                panic!("{}", divergence);
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::segment::Segment;
+use crate::RipString;
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign};
+
+impl RipString {
+    /// Moves the text from `at` on into a new string with the same settings. Only the
+    /// segment containing `at` is split, the following ones are moved as they are.
+    /// Panics if `at` is past the end.
+    pub fn split_off(&mut self, at: usize) -> RipString {
+        self.check_or_panic(&(at..at));
+        let seg_index = self.find_segment(at);
+        let mut tail = Vec::with_capacity(self.nodes.len() - seg_index);
+        let node = &mut self.nodes[seg_index];
+        let head_len = at - node.index();
+        tail.push(Segment::new(0, node.modify(|tp| tp.split(head_len))));
+        tail.extend(self.nodes.drain(seg_index + 1..));
+        self.finish_edit(seg_index);
+        self.with_nodes(tail)
+    }
+
+    /// Shortens the string to `new_len` units. Does nothing if it is not shorter already.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.len() {
+            return;
+        }
+        let seg_index = self.find_segment(new_len);
+        let node = &mut self.nodes[seg_index];
+        let keep = new_len - node.index();
+        node.modify(|tp| tp.split(keep));
+        self.nodes.truncate(seg_index + 1);
+        self.finish_edit(seg_index);
+    }
+
+    /// Removes all text, keeping the settings.
+    pub fn clear(&mut self) {
+        self.reset_nodes(Vec::new());
+    }
+
+    /// Moves the segments of `other` to the end of this string. The two segments
+    /// meeting at the boundary are merged when one of them is small.
+    pub fn append(&mut self, other: RipString) {
+        let seg_index = self.nodes.len() - 1;
+        let mut nodes = other.nodes.into_iter();
+        if let Some(first) = nodes.next() {
+            let first = first.into_tp();
+            if let Some(first) = self.nodes[seg_index].modify(|tp| tp.merge_small(first)) {
+                self.nodes.push(Segment::new(0, first));
+            }
+        }
+        self.nodes.extend(nodes);
+        self.finish_edit(seg_index);
+    }
+
+    /// Joins the strings in order. The result keeps the settings of the first one.
+    pub fn concat<I: IntoIterator<Item = RipString>>(parts: I) -> RipString {
+        let mut parts = parts.into_iter();
+        let mut rip = parts.next().unwrap_or_default();
+        for part in parts {
+            rip.append(part);
+        }
+        rip
+    }
+
+    /// Returns the text repeated `n` times, built from copies of the segments. The copy
+    /// is doubled about log2(n) times, appending it to the result for every set bit of `n`.
+    pub fn repeat(&self, n: usize) -> RipString {
+        let mut rip = self.with_nodes(Vec::new());
+        let mut block = self.clone();
+        let mut n = n;
+        while n > 0 {
+            if n % 2 == 1 {
+                rip.append(block.clone());
+            }
+            n /= 2;
+            if n > 0 {
+                block.append(block.clone());
+            }
+        }
+        rip
+    }
+
+    /// Creates a string with the settings of this one from segments in text order.
+    fn with_nodes(&self, nodes: Vec<Segment>) -> RipString {
+        let mut rip = RipString {
+            nodes: Vec::new(),
+            last_edit: 0,
+            segmenter: self.segmenter,
+            #[cfg(feature = "normalization")]
+            normalize_on_insert: self.normalize_on_insert,
+            #[cfg(feature = "word-dictionary")]
+            word_dictionary: self.word_dictionary,
+        };
+        rip.reset_nodes(nodes);
+        rip
+    }
+}
+
+impl Add<&str> for RipString {
+    type Output = RipString;
+
+    fn add(mut self, other: &str) -> RipString {
+        self.push_str(other);
+        self
+    }
+}
+
+impl Add for RipString {
+    type Output = RipString;
+
+    fn add(mut self, other: RipString) -> RipString {
+        self.append(other);
+        self
+    }
+}
+
+impl AddAssign<&str> for RipString {
+    fn add_assign(&mut self, other: &str) {
+        self.push_str(other);
+    }
+}
+
+impl AddAssign for RipString {
+    fn add_assign(&mut self, other: RipString) {
+        self.append(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_split_off() {
+        let mut a = RipString::from("Hello мир 🏡");
+        let b = a.split_off(6);
+        assert_eq!(a.to_string(), "Hello ");
+        assert_eq!(b.to_string(), "мир 🏡");
+        assert_eq!(a.clone().split_off(0).to_string(), "Hello ");
+        assert!(a.split_off(6).is_empty());
+
+        let text = "line мир 🏡\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        let mut b = a.split_off(1500);
+        let at = text.char_indices().nth(1500).unwrap().0;
+        assert_eq!(a.to_string(), text[..at]);
+        assert_eq!(b.to_string(), text[at..]);
+        assert_eq!((a.validate(), b.validate()), (Ok(()), Ok(())));
+        b.edit(0..0, "!");
+        assert_eq!(b.to_string()[1..], text[at..]);
+    }
+
+    #[test]
+    fn test_truncate_clear() {
+        let text = "line мир 🏡\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        a.truncate(5000);
+        assert_eq!(a.len(), 3300);
+        a.truncate(1501);
+        assert_eq!(
+            a.to_string(),
+            text[..text.char_indices().nth(1501).unwrap().0]
+        );
+        assert_eq!(a.validate(), Ok(()));
+        a.edit(1500..1501, "!");
+        a.truncate(0);
+        assert!(a.is_empty());
+        assert_eq!(a.validate(), Ok(()));
+
+        let mut b = RipString::from("Hello мир");
+        b.clear();
+        assert_eq!(b.to_string(), "");
+        b.push_str("ok");
+        assert_eq!(b.to_string(), "ok");
+    }
+
+    #[test]
+    fn test_repeat() {
+        let a = RipString::from("ab мир 🏡\n");
+        assert_eq!(a.repeat(3).to_string(), "ab мир 🏡\n".repeat(3));
+        assert!(a.repeat(0).is_empty());
+
+        let b = a.repeat(500);
+        assert_eq!(b.len(), 4500);
+        assert_eq!(b.validate(), Ok(()));
+        assert_eq!(RipString::new().repeat(5).to_string(), "");
+
+        let n = 100_003;
+        let b = a.repeat(n);
+        assert_eq!(b.len(), 9 * n);
+        assert_eq!(b.to_string(), "ab мир 🏡\n".repeat(n));
+        assert_eq!(b.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = RipString::from("Hello ");
+        a.append(RipString::from("мир"));
+        a.append(RipString::new());
+        assert_eq!(a.to_string(), "Hello мир");
+        assert_eq!(a.len(), 9);
+
+        let mut b = RipString::new();
+        b.append(RipString::from("🏡"));
+        assert_eq!(b.to_string(), "🏡");
+        assert_eq!(b.validate(), Ok(()));
+
+        let text = "line мир 🏡\n".repeat(300);
+        let mut c = RipString::from(text.as_str());
+        let tail = c.split_off(1234);
+        c.append(tail);
+        assert_eq!(c.to_string(), text);
+        assert_eq!(c.validate(), Ok(()));
+
+        let parts = ["a", "б", "🏡", ""]
+            .iter()
+            .map(|part| RipString::from(*part));
+        assert_eq!(RipString::concat(parts).to_string(), "aб🏡");
+        assert!(RipString::concat(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_add() {
+        let a = RipString::from("Hello") + ", мир" + RipString::from(" 🏡");
+        assert_eq!(a, "Hello, мир 🏡");
+
+        let text = "ab мир 🏡\n".repeat(200);
+        let mut b = RipString::new();
+        b += text.as_str();
+        b += RipString::from(text.as_str());
+        b += "!";
+        assert_eq!(b, text.repeat(2) + "!");
+        assert_eq!(b.validate(), Ok(()));
+    }
+}
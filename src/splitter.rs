@@ -1,86 +1,393 @@
+//! Segmentation of text into the runs a `RipString` stores.
+//!
+//! Text is cut into blocks of at most `MAX_BLOCK_SIZE` bytes, preferably after a line
+//! break, and every block into runs of ASCII bytes, single-char clusters and
+//! multi-char clusters.
+
 use crate::segment::SegmentType;
+use crate::segmenter::{Graphemes, Segmenter};
+use crate::{RipError, RipString};
 use alloc::collections::VecDeque;
-use alloc::string::ToString;
-use alloc::vec;
+use alloc::fmt::{Display, Formatter};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::cmp::min;
-use core::mem;
+use core::str;
 use memchr::memrchr;
-use seshat::unicode::Segmentation;
 
-pub const MAX_BLOCK_SIZE: usize = 1024;
-pub const MIN_BLOCK_SIZE: usize = 512;
+pub(crate) const MAX_BLOCK_SIZE: usize = 1024;
+pub(crate) const MIN_BLOCK_SIZE: usize = 512;
+
+/// Storage kind of a partition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// ASCII text, one unit per byte.
+    Ascii,
+    /// Clusters made of a single non-ASCII char, one unit per char.
+    Utf8,
+    /// Clusters made of several chars, one unit per cluster.
+    Unicode,
+}
+
+/// Tuning of the splitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitterConfig {
+    /// Maximum size of a block in bytes, capped at 1024 so that every block fits in a
+    /// segment.
+    pub max_block_size: usize,
+    /// Minimum size of a block in bytes, unless the text is shorter. At most half the
+    /// maximum size.
+    pub min_block_size: usize,
+    /// Byte after which a block preferably ends, `\n` by default.
+    pub split_byte: Option<u8>,
+    /// End blocks after ASCII whitespace when `split_byte` is not found.
+    pub split_on_words: bool,
+    /// Never end a block inside a grapheme cluster.
+    pub keep_clusters: bool,
+}
+
+impl Default for SplitterConfig {
+    fn default() -> Self {
+        SplitterConfig {
+            max_block_size: MAX_BLOCK_SIZE,
+            min_block_size: MIN_BLOCK_SIZE,
+            split_byte: Some(b'\n'),
+            split_on_words: false,
+            keep_clusters: false,
+        }
+    }
+}
+
+/// A run of text of a single kind, as stored in one segment of a `RipString`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    tp: SegmentType,
+}
 
-pub struct Splitter<'a> {
+impl Partition {
+    pub fn kind(&self) -> SegmentKind {
+        self.tp.kind()
+    }
+
+    /// Returns the length in units.
+    pub fn len(&self) -> usize {
+        self.tp.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tp.is_empty()
+    }
+
+    /// Calls `f` with the text of every unit.
+    pub fn for_each_unit<F: FnMut(&str)>(&self, f: F) {
+        self.tp.for_each_unit(0..self.tp.len(), f)
+    }
+}
+
+impl Display for Partition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.tp, f)
+    }
+}
+
+/// Iterator over the partitions of a text, in order.
+pub struct Partitions<'a> {
+    splitter: Splitter<'a>,
+}
+
+impl<'a> Partitions<'a> {
+    pub fn new(text: &'a str, segmenter: &'a dyn Segmenter) -> Partitions<'a> {
+        Partitions::with_config(text, segmenter, SplitterConfig::default())
+    }
+
+    pub fn with_config(
+        text: &'a str,
+        segmenter: &'a dyn Segmenter,
+        config: SplitterConfig,
+    ) -> Partitions<'a> {
+        Partitions {
+            splitter: Splitter::with_config(text, segmenter, config),
+        }
+    }
+}
+
+impl<'a> Iterator for Partitions<'a> {
+    type Item = Partition;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.splitter.next().map(|tp| Partition { tp })
+    }
+}
+
+/// Push-based splitter for text arriving in chunks, e.g. from a download. Blocks are
+/// emitted as soon as enough text follows them to cut them as `Partitions` would.
+pub struct StreamSplitter {
+    pending: Vec<u8>,
+    consumed: usize,
+    segmenter: &'static dyn Segmenter,
+    config: SplitterConfig,
+}
+
+impl StreamSplitter {
+    pub fn new(segmenter: &'static dyn Segmenter) -> StreamSplitter {
+        StreamSplitter::with_config(segmenter, SplitterConfig::default())
+    }
+
+    pub fn with_config(
+        segmenter: &'static dyn Segmenter,
+        config: SplitterConfig,
+    ) -> StreamSplitter {
+        StreamSplitter {
+            pending: Vec::new(),
+            consumed: 0,
+            segmenter,
+            config: Splitter::with_config("", segmenter, config).config,
+        }
+    }
+
+    /// Feeds the next chunk and returns the partitions of the blocks it completed. A
+    /// UTF-8 sequence may be split between chunks.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<Partition>, RipError> {
+        self.pending.extend_from_slice(chunk);
+        let valid = match str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(RipError::InvalidUtf8(self.consumed + err.valid_up_to())),
+        };
+
+        let lookahead = self.config.max_block_size + self.config.min_block_size;
+        let mut partitions = Vec::new();
+        let mut start = 0;
+        while valid - start >= lookahead {
+            let text = str::from_utf8(&self.pending[start..valid]).unwrap_or_default();
+            let end = Splitter::with_config(text, self.segmenter, self.config).block_end();
+            partitions.extend(Partitions::with_config(
+                &text[..end],
+                self.segmenter,
+                self.config,
+            ));
+            start += end;
+        }
+        self.pending.drain(..start);
+        self.consumed += start;
+        Ok(partitions)
+    }
+
+    /// Returns the partitions of the remaining text.
+    pub fn finish(self) -> Result<Vec<Partition>, RipError> {
+        let text = str::from_utf8(&self.pending)
+            .map_err(|err| RipError::InvalidUtf8(self.consumed + err.valid_up_to()))?;
+        Ok(Partitions::with_config(text, self.segmenter, self.config).collect())
+    }
+}
+
+impl RipString {
+    /// Creates a string from partitions in text order.
+    pub fn from_partitions<I: IntoIterator<Item = Partition>>(
+        partitions: I,
+        segmenter: &'static dyn Segmenter,
+    ) -> RipString {
+        RipString::from_segment_types(partitions.into_iter().map(|p| p.tp), segmenter)
+    }
+
+    /// Reads UTF-8 text to the end, splitting it while it arrives.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> std::io::Result<RipString> {
+        use std::io::{Error, ErrorKind};
+
+        let mut splitter = StreamSplitter::new(crate::segmenter::DEFAULT);
+        let mut partitions = Vec::new();
+        let mut buf = [0; 8192];
+        loop {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            let done = splitter
+                .push(&buf[..read])
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+            partitions.extend(done);
+        }
+        partitions.extend(
+            splitter
+                .finish()
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))?,
+        );
+        Ok(RipString::from_partitions(
+            partitions,
+            crate::segmenter::DEFAULT,
+        ))
+    }
+}
+
+/// Cuts ASCII text into blocks as `Splitter` would. The first block keeps the
+/// allocation of `text`.
+pub(crate) fn split_ascii(text: String, segmenter: &dyn Segmenter) -> Vec<SegmentType> {
+    let mut starts = Vec::new();
+    let mut splitter = Splitter::new(&text, segmenter);
+    let mut start = 0;
+    while let Some(block) = splitter.next_block() {
+        starts.push(start);
+        start += block.len();
+    }
+
+    let mut bytes = text.into_bytes();
+    let mut blocks = Vec::with_capacity(starts.len());
+    for start in starts.into_iter().skip(1).rev() {
+        blocks.push(SegmentType::Ascii(bytes.split_off(start)));
+    }
+    blocks.push(SegmentType::Ascii(bytes));
+    blocks.reverse();
+    blocks
+}
+
+pub(crate) struct Splitter<'a> {
     buffer: &'a str,
     segments: VecDeque<SegmentType>,
+    segmenter: &'a dyn Segmenter,
+    config: SplitterConfig,
 }
 
 impl<'a> Splitter<'a> {
-    pub fn new(buffer: &'a str) -> Splitter<'a> {
+    pub fn new(buffer: &'a str, segmenter: &'a dyn Segmenter) -> Splitter<'a> {
+        Splitter::with_config(buffer, segmenter, SplitterConfig::default())
+    }
+
+    pub fn with_config(
+        buffer: &'a str,
+        segmenter: &'a dyn Segmenter,
+        config: SplitterConfig,
+    ) -> Splitter<'a> {
+        let max_block_size = config.max_block_size.clamp(1, MAX_BLOCK_SIZE);
         Splitter {
             buffer,
             segments: VecDeque::new(),
+            segmenter,
+            config: SplitterConfig {
+                max_block_size,
+                min_block_size: config.min_block_size.clamp(1, (max_block_size / 2).max(1)),
+                ..config
+            },
+        }
+    }
+
+    /// Returns the end of the next block of a buffer longer than the maximum block size.
+    fn block_end(&self) -> usize {
+        let bytes = self.buffer.as_bytes();
+        let min_size = self.config.min_block_size;
+        let mut split_point = min(self.config.max_block_size, bytes.len() - min_size);
+        let window = &bytes[min_size - 1..split_point];
+        let preferred = match self.config.split_byte {
+            Some(byte) => memrchr(byte, window),
+            None => None,
+        };
+        let preferred = preferred.or_else(|| {
+            if self.config.split_on_words {
+                window.iter().rposition(u8::is_ascii_whitespace)
+            } else {
+                None
+            }
+        });
+
+        match preferred {
+            Some(pos) => split_point = min_size + pos,
+            None => {
+                let mut end = split_point;
+                while !self.buffer.is_char_boundary(end) {
+                    end -= 1;
+                }
+                // Blocks smaller than a char still have to make progress.
+                if end == 0 {
+                    end = split_point;
+                    while !self.buffer.is_char_boundary(end) {
+                        end += 1;
+                    }
+                }
+                split_point = end;
+            }
+        }
+        // Keep `\r\n` in one block, also when the preferred byte is the `\r`.
+        if split_point > 1 && bytes[split_point - 1] == b'\r' && bytes[split_point] == b'\n' {
+            split_point -= 1;
+        }
+
+        if self.config.keep_clusters {
+            let mut end = 0;
+            for grapheme in Graphemes::new(self.buffer, self.segmenter) {
+                if end + grapheme.len() > split_point {
+                    break;
+                }
+                end += grapheme.len();
+            }
+            if end > 0 {
+                split_point = end;
+            }
         }
+        split_point
     }
 }
 
 impl<'a> Splitter<'a> {
-    pub fn make_segments(&mut self, split_point: usize) -> Option<SegmentType> {
-        let str = &self.buffer[..split_point];
-        self.buffer = &self.buffer[split_point..];
-
-        let mut current_seq = SegmentType::Ascii(vec![]);
-        for seq in str.break_graphemes() {
-            if seq.is_ascii() {
-                if let SegmentType::Ascii(ascii_seq) = &mut current_seq {
-                    ascii_seq.extend_from_slice(seq.as_bytes());
+    /// Cuts the next block off the buffer.
+    fn next_block(&mut self) -> Option<&'a str> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let end = if self.buffer.len() <= self.config.max_block_size {
+            self.buffer.len()
+        } else {
+            self.block_end()
+        };
+        let (block, rest) = self.buffer.split_at(end);
+        self.buffer = rest;
+        Some(block)
+    }
+
+    /// Splits a block into runs of a single kind. Non-alphabetic ASCII clusters join a
+    /// preceding run of single-char clusters.
+    fn runs(&self, block: &'a str) -> Vec<(&'a str, SegmentKind)> {
+        let mut runs = Vec::new();
+        let mut start = 0;
+        let mut current: Option<SegmentKind> = None;
+        let mut end = 0;
+        for seq in Graphemes::new(block, self.segmenter) {
+            let kind = if seq.is_ascii() {
+                let is_alphabetic = seq.as_bytes().iter().any(|b| b.is_ascii_alphabetic());
+                if current == Some(SegmentKind::Utf8) && !is_alphabetic {
+                    SegmentKind::Utf8
                 } else {
-                    if let SegmentType::Utf8(vars) = &mut current_seq {
-                        let is_alphabetic = seq.as_bytes().iter().any(|b| b.is_ascii_alphabetic());
-                        if !is_alphabetic {
-                            vars.extend(seq.chars());
-                            continue;
-                        }
-                    }
-                    let is_current_empty = current_seq.is_empty();
-                    let prev = mem::replace(
-                        &mut current_seq,
-                        SegmentType::Ascii(seq.as_bytes().to_vec()),
-                    );
-                    if !is_current_empty {
-                        self.segments.push_front(prev)
-                    }
+                    SegmentKind::Ascii
                 }
             } else if seq.len() > 2 {
-                if let SegmentType::Unicode(unicode_seq) = &mut current_seq {
-                    unicode_seq.push(seq.to_string());
-                } else {
-                    let is_current_empty = current_seq.is_empty();
-                    let prev = mem::replace(
-                        &mut current_seq,
-                        SegmentType::Unicode(vec![seq.to_string()]),
-                    );
-                    if !is_current_empty {
-                        self.segments.push_front(prev)
-                    }
-                }
-            } else if let SegmentType::Utf8(char_seq) = &mut current_seq {
-                char_seq.extend(seq.chars());
+                SegmentKind::Unicode
             } else {
-                let is_current_empty = current_seq.is_empty();
-                let prev = mem::replace(&mut current_seq, SegmentType::Utf8(seq.chars().collect()));
-                if !is_current_empty {
-                    self.segments.push_front(prev)
-                }
+                SegmentKind::Utf8
+            };
+            if let Some(current) = current.filter(|current| *current != kind) {
+                runs.push((&block[start..end], current));
+                start = end;
             }
+            current = Some(kind);
+            end += seq.len();
         }
-
-        if !current_seq.is_empty() {
-            self.segments
-                .push_front(mem::replace(&mut current_seq, SegmentType::Ascii(vec![])));
+        if let Some(current) = current {
+            runs.push((&block[start..end], current));
         }
+        runs
+    }
 
-        self.segments.pop_back()
+    fn make_segment(&self, run: &str, kind: SegmentKind) -> SegmentType {
+        match kind {
+            SegmentKind::Ascii => SegmentType::Ascii(run.as_bytes().to_vec()),
+            SegmentKind::Utf8 => SegmentType::Utf8(run.chars().collect()),
+            SegmentKind::Unicode => SegmentType::Unicode(
+                Graphemes::new(run, self.segmenter)
+                    .map(ToString::to_string)
+                    .collect(),
+            ),
+        }
     }
 }
 
@@ -88,38 +395,59 @@ impl<'a> Iterator for Splitter<'a> {
     type Item = SegmentType;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.buffer.is_empty() && self.segments.is_empty() {
-            return None;
-        }
-
         if self.segments.is_empty() {
-            if self.buffer.len() <= MAX_BLOCK_SIZE {
-                return self.make_segments(self.buffer.len());
+            let block = self.next_block()?;
+            for (run, kind) in self.runs(block) {
+                let segment = self.make_segment(run, kind);
+                self.segments.push_back(segment);
             }
+        }
+        self.segments.pop_front()
+    }
+}
 
-            let mut split_point = min(MAX_BLOCK_SIZE, self.buffer.len() - MIN_BLOCK_SIZE);
-            match memrchr(
-                b'\n',
-                &self.buffer.as_bytes()[MIN_BLOCK_SIZE - 1..split_point],
-            ) {
-                Some(pos) => self.make_segments(MIN_BLOCK_SIZE + pos),
-                None => {
-                    while !self.buffer.is_char_boundary(split_point) {
-                        split_point -= 1;
-                    }
-                    self.make_segments(split_point)
-                }
-            }
-        } else {
-            self.segments.remove(self.segments.len() - 1)
+/// Iterator over the partitions of a text as slices of it, for callers which store
+/// the text themselves.
+pub struct BorrowedPartitions<'a> {
+    splitter: Splitter<'a>,
+    runs: VecDeque<(&'a str, SegmentKind)>,
+}
+
+impl<'a> BorrowedPartitions<'a> {
+    pub fn new(text: &'a str, segmenter: &'a dyn Segmenter) -> BorrowedPartitions<'a> {
+        BorrowedPartitions::with_config(text, segmenter, SplitterConfig::default())
+    }
+
+    pub fn with_config(
+        text: &'a str,
+        segmenter: &'a dyn Segmenter,
+        config: SplitterConfig,
+    ) -> BorrowedPartitions<'a> {
+        BorrowedPartitions {
+            splitter: Splitter::with_config(text, segmenter, config),
+            runs: VecDeque::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for BorrowedPartitions<'a> {
+    type Item = (&'a str, SegmentKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.runs.is_empty() {
+            let block = self.splitter.next_block()?;
+            self.runs = self.splitter.runs(block).into();
         }
+        self.runs.pop_front()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::segment::SegmentType;
+    use crate::segmenter::DEFAULT;
     use crate::splitter::Splitter;
+    use alloc::format;
     use alloc::string::{String, ToString};
     use alloc::vec;
     use alloc::vec::Vec;
@@ -127,7 +455,9 @@ mod tests {
     fn split_check(partition: &[&str]) {
         let text: String = partition.iter().map(|p| p.to_string()).collect();
 
-        let actual: Vec<_> = Splitter::new(&text).map(|s| s.to_string()).collect();
+        let actual: Vec<_> = Splitter::new(&text, DEFAULT)
+            .map(|s| s.to_string())
+            .collect();
         assert_eq!(partition, &actual);
     }
 
@@ -164,7 +494,7 @@ Ladyship these jointure several shed they forming warmly folly. \
 Servants consider fat his cannot winding who brother greatly certainty precaution deal dashwoods. \
 Admitting left attention remarkably spoil woody disposed change exercise matter period females weddings world found. \
 ";
-        let partition = Splitter::new(text).next().unwrap();
+        let partition = Splitter::new(text, DEFAULT).next().unwrap();
         if let SegmentType::Ascii(ascii) = partition {
             assert_eq!(text, String::from_utf8_lossy(&ascii).as_ref());
         } else {
@@ -176,7 +506,7 @@ Admitting left attention remarkably spoil woody disposed change exercise matter
     fn test_utf8_segments() {
         let text = "Не следует, однако забывать, что дальнейшее развитие различных форм деятельности способствует подготовки и реализации форм развития. \
     Равным образом постоянный количественный рост и сфера нашей активности играет важную роль в формировании системы обучения кадров, соответствует насущным потребностям.";
-        let partition = Splitter::new(text).next().unwrap();
+        let partition = Splitter::new(text, DEFAULT).next().unwrap();
         if let SegmentType::Utf8(ascii) = partition {
             assert_eq!(text, &ascii.into_iter().collect::<String>());
         } else {
@@ -184,6 +514,13 @@ Admitting left attention remarkably spoil woody disposed change exercise matter
         }
     }
 
+    #[test]
+    fn test_crlf_is_not_split() {
+        let text = format!("{}\r\n{}", "a".repeat(1023), "b".repeat(975));
+        split_check(&[&"a".repeat(1023), &format!("\r\n{}", "b".repeat(975))]);
+        assert_eq!(Splitter::new(&text, DEFAULT).count(), 2);
+    }
+
     fn ascii(str: &str) -> SegmentType {
         SegmentType::Ascii(str.as_bytes().to_vec())
     }
@@ -196,7 +533,7 @@ Admitting left attention remarkably spoil woody disposed change exercise matter
         👨‍👩‍👧‍👦\
 формировании системы обучения кадров.\
     ";
-        let partition = Splitter::new(text).collect::<Vec<_>>();
+        let partition = Splitter::new(text, DEFAULT).collect::<Vec<_>>();
         assert_eq!(partition,
                    vec![
                        SegmentType::Utf8("Таким образом реализация намеченных плановых заданий позволяет оценить значение новых предложений".chars().collect()),
@@ -225,4 +562,186 @@ Admitting left attention remarkably spoil woody disposed change exercise matter
                    ]
         )
     }
+
+    #[test]
+    fn test_partitions() {
+        use crate::splitter::{Partitions, SegmentKind};
+
+        let partitions = Partitions::new("Hi мир 👨‍👩‍👧!", DEFAULT).collect::<Vec<_>>();
+        let kinds = partitions.iter().map(|p| p.kind()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SegmentKind::Ascii,
+                SegmentKind::Utf8,
+                SegmentKind::Unicode,
+                SegmentKind::Ascii
+            ]
+        );
+        assert_eq!(partitions[1].to_string(), "мир ");
+        assert_eq!(partitions[2].len(), 1);
+
+        let mut units = Vec::new();
+        partitions[0].for_each_unit(|unit| units.push(unit.to_string()));
+        assert_eq!(units, ["H", "i", " "]);
+    }
+
+    #[test]
+    fn test_splitter_config() {
+        use crate::splitter::{Partitions, SplitterConfig};
+        use crate::RipString;
+
+        let config = SplitterConfig {
+            max_block_size: 12,
+            min_block_size: 4,
+            split_byte: Some(b','),
+            split_on_words: true,
+            ..SplitterConfig::default()
+        };
+        let blocks = |text| {
+            Partitions::with_config(text, DEFAULT, config)
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            blocks("one two, three four five"),
+            ["one two,", " three four ", "five"]
+        );
+        assert_eq!(
+            blocks("one two three four five"),
+            ["one two ", "three four ", "five"]
+        );
+
+        let text = format!("aaaaaaae\u{301}{}", "b".repeat(8));
+        let config = SplitterConfig {
+            max_block_size: 8,
+            min_block_size: 4,
+            split_byte: None,
+            ..SplitterConfig::default()
+        };
+        assert_eq!(
+            RipString::with_splitter_config(&text, DEFAULT, config).len(),
+            17
+        );
+        let config = SplitterConfig {
+            keep_clusters: true,
+            ..config
+        };
+        let a = RipString::with_splitter_config(&text, DEFAULT, config);
+        assert_eq!(a.len(), 16);
+        assert_eq!(a.to_string(), text);
+
+        let config = SplitterConfig {
+            max_block_size: 1,
+            ..SplitterConfig::default()
+        };
+        assert_eq!(Partitions::with_config("мир", DEFAULT, config).count(), 3);
+    }
+
+    #[test]
+    fn test_oversized_config() {
+        use crate::splitter::SplitterConfig;
+        use crate::RipString;
+
+        let config = SplitterConfig {
+            max_block_size: 4096,
+            min_block_size: 2048,
+            ..SplitterConfig::default()
+        };
+        let text = "word ".repeat(1000);
+        let mut a = RipString::with_splitter_config(&text, DEFAULT, config);
+        assert_eq!(a.validate(), Ok(()));
+        a.insert(3, "мир 🏡");
+        a.remove(2000..2100);
+        a.insert(4000, &"x".repeat(3000));
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(a.len(), 7905);
+    }
+
+    #[test]
+    fn test_crlf_at_window_edge() {
+        use crate::splitter::{Partitions, SplitterConfig};
+
+        let words = SplitterConfig {
+            max_block_size: 8,
+            min_block_size: 4,
+            split_byte: None,
+            split_on_words: true,
+            ..SplitterConfig::default()
+        };
+        let carriage_return = SplitterConfig {
+            split_byte: Some(b'\r'),
+            split_on_words: false,
+            ..words
+        };
+        for config in [words, carriage_return] {
+            let blocks = Partitions::with_config("one two\r\nthree four", DEFAULT, config)
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(blocks, ["one two", "\r\nthree ", "four"]);
+        }
+    }
+
+    #[test]
+    fn test_stream_splitter() {
+        use crate::splitter::{Partitions, StreamSplitter};
+        use crate::{RipError, RipString};
+
+        let text = format!(
+            "{}\n{}мир 🏡\r\n{}",
+            "a".repeat(700),
+            "é".repeat(600),
+            "b".repeat(900)
+        );
+        let expected = Partitions::new(&text, DEFAULT).collect::<Vec<_>>();
+        for chunk_size in [1, 7, 100, 5000] {
+            let mut splitter = StreamSplitter::new(DEFAULT);
+            let mut partitions = Vec::new();
+            for chunk in text.as_bytes().chunks(chunk_size) {
+                partitions.extend(splitter.push(chunk).unwrap());
+            }
+            partitions.extend(splitter.finish().unwrap());
+            assert_eq!(partitions, expected);
+        }
+
+        let a = RipString::from_partitions(expected, DEFAULT);
+        assert_eq!(a.to_string(), text);
+
+        let mut splitter = StreamSplitter::new(DEFAULT);
+        assert!(splitter.push(&[b'a', 0xd0]).unwrap().is_empty());
+        assert_eq!(splitter.push(b"b"), Err(RipError::InvalidUtf8(1)));
+        let mut splitter = StreamSplitter::new(DEFAULT);
+        splitter.push(&[b'a', 0xd0]).unwrap();
+        assert_eq!(splitter.finish(), Err(RipError::InvalidUtf8(1)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_reader() {
+        use crate::RipString;
+
+        let text = "line\n".repeat(2000) + "мир";
+        let a = RipString::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(a.to_string(), text);
+        assert!(RipString::from_reader(&[0xff][..]).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_partitions() {
+        use crate::splitter::{BorrowedPartitions, Partitions};
+
+        let text = format!("{}Hi, мир! 👨‍👩‍👧 €5\r\n", "ab\n".repeat(400));
+        let borrowed = BorrowedPartitions::new(&text, DEFAULT).collect::<Vec<_>>();
+        let owned = Partitions::new(&text, DEFAULT)
+            .map(|p| (p.to_string(), p.kind()))
+            .collect::<Vec<_>>();
+        assert_eq!(borrowed.len(), owned.len());
+        for ((run, kind), (text, owned_kind)) in borrowed.iter().zip(&owned) {
+            assert_eq!((*run, *kind), (text.as_str(), *owned_kind));
+        }
+        assert_eq!(
+            borrowed.iter().map(|(run, _)| *run).collect::<String>(),
+            text
+        );
+    }
 }
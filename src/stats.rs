@@ -0,0 +1,83 @@
+use crate::RipString;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextStats {
+    pub graphemes: usize,
+    pub chars: usize,
+    pub words: usize,
+    pub sentences: usize,
+    pub lines: usize,
+}
+
+pub(crate) fn is_terminator(unit: &str) -> bool {
+    matches!(unit, "." | "!" | "?" | "…" | "。" | "！" | "？")
+}
+
+/// Closing punctuation which may follow a sentence terminator.
+pub(crate) fn is_closing(unit: &str) -> bool {
+    matches!(unit, "\"" | "'" | ")" | "]" | "»" | "”" | "’" | "」")
+}
+
+impl RipString {
+    /// Returns counts of grapheme clusters, chars, words, sentences and lines.
+    ///
+    /// A sentence ends with a terminator followed by whitespace or the end of the text.
+    pub fn stats_text(&self) -> TextStats {
+        let mut stats = TextStats {
+            chars: self.len_chars(),
+            words: self.words().count(),
+            lines: self.line_count(),
+            ..TextStats::default()
+        };
+
+        let mut in_sentence = false;
+        let mut terminated = false;
+        self.for_each_unit(0..self.len(), |unit| {
+            if unit.chars().all(char::is_whitespace) {
+                if terminated {
+                    stats.sentences += 1;
+                    in_sentence = false;
+                    terminated = false;
+                }
+            } else if is_terminator(unit) {
+                terminated = in_sentence;
+            } else if !(terminated && is_closing(unit)) {
+                in_sentence = true;
+                terminated = false;
+            }
+        });
+        if in_sentence {
+            stats.sentences += 1;
+        }
+
+        stats.graphemes = self.len_graphemes();
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipString, TextStats};
+
+    #[test]
+    fn test_stats_text() {
+        let a = RipString::from("Hello, мир! It costs 3.14 «€».\r\n\"Really?\" Yes...\n👨‍👩‍👧‍👦 ok");
+        assert_eq!(
+            a.stats_text(),
+            TextStats {
+                graphemes: 52,
+                chars: 59,
+                words: 9,
+                sentences: 5,
+                lines: 3,
+            }
+        );
+        assert_eq!(
+            RipString::new().stats_text(),
+            TextStats {
+                lines: 1,
+                ..TextStats::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Differential testing helpers.
+
+use crate::RipString;
+use alloc::string::{String, ToString};
+use core::ops::Range;
+
+/// A rope which mirrors every operation onto a plain `String` and panics as soon as
+/// the two diverge.
+#[derive(Debug)]
+pub struct ShadowRope {
+    rope: RipString,
+    shadow: String,
+}
+
+impl ShadowRope {
+    pub fn new(text: &str) -> ShadowRope {
+        let shadow = ShadowRope {
+            rope: RipString::from(text),
+            shadow: String::from(text),
+        };
+        shadow.check();
+        shadow
+    }
+
+    pub fn rope(&self) -> &RipString {
+        &self.rope
+    }
+
+    pub fn shadow(&self) -> &str {
+        &self.shadow
+    }
+
+    pub fn edit(&mut self, range: Range<usize>, text: &str) {
+        self.mirror(range.clone(), text);
+        self.rope.edit(range, text);
+        self.check();
+    }
+
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        self.mirror(range.clone(), text);
+        self.rope.replace(range, text);
+        self.check();
+    }
+
+    /// Runs an arbitrary operation on both sides and compares the results.
+    pub fn apply<R, S>(&mut self, rope_op: R, shadow_op: S)
+    where
+        R: FnOnce(&mut RipString),
+        S: FnOnce(&mut String),
+    {
+        rope_op(&mut self.rope);
+        shadow_op(&mut self.shadow);
+        self.check();
+    }
+
+    /// Panics if the rope and the shadow string differ.
+    pub fn check(&self) {
+        let text = self.rope.to_string();
+        assert_eq!(text, self.shadow, "rope diverged from its shadow");
+        assert_eq!(
+            self.rope.index_to_byte(self.rope.len()),
+            self.shadow.len(),
+            "rope indices diverged from its content"
+        );
+    }
+
+    fn mirror(&mut self, range: Range<usize>, text: &str) {
+        let bytes = self.rope.index_to_byte(range.start)..self.rope.index_to_byte(range.end);
+        self.shadow.replace_range(bytes, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::ShadowRope;
+
+    #[test]
+    fn test_shadow_rope() {
+        let mut a = ShadowRope::new("Hello мир 🏡");
+        a.edit(11..11, "!");
+        a.edit(0..5, "Bye");
+        a.replace(4..7, "world");
+        a.apply(
+            |rope| rope.make_uppercase(),
+            |text| *text = text.to_uppercase(),
+        );
+        assert_eq!(a.shadow(), "BYE WORLD 🏡!");
+    }
+
+    #[test]
+    #[should_panic(expected = "rope diverged from its shadow")]
+    fn test_shadow_rope_divergence() {
+        let mut a = ShadowRope::new("abc");
+        a.apply(
+            |rope| rope.edit(0..1, "x"),
+            |text| text.replace_range(0..1, "y"),
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::segment::Segment;
+use crate::RipString;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+/// A group of edits which is undone unless committed. Dropping the transaction, for
+/// example when returning early on an error, rolls it back.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    rip: &'a mut RipString,
+    saved: Option<(Vec<Segment>, usize)>,
+}
+
+impl RipString {
+    /// Starts a transaction. The segments are copied so that they can be restored.
+    pub fn begin(&mut self) -> Transaction<'_> {
+        let saved = (self.nodes.clone(), self.last_edit);
+        Transaction {
+            rip: self,
+            saved: Some(saved),
+        }
+    }
+}
+
+impl Transaction<'_> {
+    /// Keeps the edits made in the transaction.
+    pub fn commit(mut self) {
+        self.saved = None;
+    }
+
+    /// Undoes the edits made in the transaction.
+    pub fn rollback(self) {}
+}
+
+impl Deref for Transaction<'_> {
+    type Target = RipString;
+
+    fn deref(&self) -> &RipString {
+        self.rip
+    }
+}
+
+impl DerefMut for Transaction<'_> {
+    fn deref_mut(&mut self) -> &mut RipString {
+        self.rip
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if let Some((nodes, last_edit)) = self.saved.take() {
+            self.rip.nodes = nodes;
+            self.rip.last_edit = last_edit;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipError, RipString};
+    use alloc::string::ToString;
+    use core::ops::Range;
+
+    fn apply(rip: &mut RipString, edits: &[(Range<usize>, &str)]) -> Result<(), RipError> {
+        let mut tx = rip.begin();
+        for (range, text) in edits {
+            tx.try_edit(range.clone(), text)?;
+        }
+        tx.commit();
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut a = RipString::from("Hello мир");
+        assert_eq!(apply(&mut a, &[(5..5, ","), (10..10, "!")]), Ok(()));
+        assert_eq!(a.to_string(), "Hello, мир!");
+
+        let result = apply(&mut a, &[(0..5, "Bye"), (0..0, ">"), (9..12, "")]);
+        assert_eq!(result, Err(RipError::OutOfBounds { index: 12, len: 10 }));
+        assert_eq!(a.to_string(), "Hello, мир!");
+
+        let mut tx = a.begin();
+        tx.push_str(" 🏡");
+        assert_eq!(tx.len(), 13);
+        tx.rollback();
+        assert_eq!(a.to_string(), "Hello, мир!");
+        assert_eq!(a.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::iter::Chars;
+use crate::segment::{self, SegmentType};
+use crate::splitter::MAX_BLOCK_SIZE;
+use crate::{RipError, RipString, RopeBuilder};
+use alloc::string::String;
+use core::char::{decode_utf16, REPLACEMENT_CHARACTER};
+use core::convert::Infallible;
+
+/// Iterator over the UTF-16 code units of the text. Returned by
+/// `RipString::encode_utf16`.
+pub struct EncodeUtf16<'a> {
+    chars: Chars<'a>,
+    /// Trailing surrogate of the last char.
+    pending: Option<u16>,
+}
+
+impl Iterator for EncodeUtf16<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(unit) = self.pending.take() {
+            return Some(unit);
+        }
+        let mut buf = [0; 2];
+        let units = self.chars.next()?.encode_utf16(&mut buf);
+        if let [_, low] = units {
+            self.pending = Some(*low);
+        }
+        Some(units[0])
+    }
+}
+
+impl RipString {
+    /// Decodes UTF-16 text, failing with the offset in code units of the first unpaired
+    /// surrogate.
+    pub fn from_utf16(val: &[u16]) -> Result<RipString, RipError> {
+        build_utf16(val, |offset| Err(RipError::UnpairedSurrogate(offset)))
+    }
+
+    /// Returns the offset in UTF-16 code units of the unit at `index`.
+    pub fn index_to_utf16(&self, index: usize) -> usize {
+        self.index_to_offset(index, SegmentType::utf16_len, segment::utf16_len)
+    }
+
+    /// Returns the index of the unit containing the UTF-16 offset, or the length of the
+    /// text if the offset is past its end.
+    pub fn utf16_to_index(&self, offset: usize) -> usize {
+        self.offset_to_index(offset, SegmentType::utf16_len, segment::utf16_len)
+    }
+
+    /// Decodes UTF-16 text, replacing unpaired surrogates with U+FFFD.
+    pub fn from_utf16_lossy(val: &[u16]) -> RipString {
+        match build_utf16(val, |_| Ok::<_, Infallible>(REPLACEMENT_CHARACTER)) {
+            Ok(rip) => rip,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Returns an iterator over the text encoded as UTF-16.
+    pub fn encode_utf16(&self) -> EncodeUtf16<'_> {
+        EncodeUtf16 {
+            chars: self.chars(),
+            pending: None,
+        }
+    }
+}
+
+/// Decodes UTF-16 text block by block into a rope. `unpaired` is called with the offset
+/// of every unpaired surrogate and returns the char to put in its place.
+fn build_utf16<E, F: FnMut(usize) -> Result<char, E>>(
+    val: &[u16],
+    mut unpaired: F,
+) -> Result<RipString, E> {
+    let mut builder = RopeBuilder::new();
+    let mut chunk = String::with_capacity(MAX_BLOCK_SIZE + 4);
+    let mut offset = 0;
+    for ch in decode_utf16(val.iter().copied()) {
+        let ch = match ch {
+            Ok(ch) => ch,
+            Err(_) => unpaired(offset)?,
+        };
+        offset += ch.len_utf16();
+        chunk.push(ch);
+        if chunk.len() >= MAX_BLOCK_SIZE {
+            builder.append(&chunk);
+            chunk.clear();
+        }
+    }
+    builder.append(&chunk);
+    Ok(builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipError, RipString};
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_from_utf16() {
+        let text = "Hello, мир 🏡";
+        let utf16 = text.encode_utf16().collect::<Vec<_>>();
+        assert_eq!(RipString::from_utf16(&utf16).unwrap().to_string(), text);
+
+        let broken = [0x61, 0xD83C, 0x62, 0xDFE1, 0xD83C, 0xDFE1, 0xD800];
+        assert_eq!(
+            RipString::from_utf16(&broken).unwrap_err(),
+            RipError::UnpairedSurrogate(1)
+        );
+        assert_eq!(
+            RipString::from_utf16(&broken[2..]).unwrap_err(),
+            RipError::UnpairedSurrogate(1)
+        );
+        assert_eq!(
+            RipString::from_utf16(&broken[3..]).unwrap_err(),
+            RipError::UnpairedSurrogate(0)
+        );
+        assert_eq!(
+            RipString::from_utf16(&broken[4..]).unwrap_err(),
+            RipError::UnpairedSurrogate(2)
+        );
+        assert_eq!(
+            RipString::from_utf16_lossy(&broken).to_string(),
+            "a\u{fffd}b\u{fffd}🏡\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        let a = RipString::from("aé 🏡e\u{301}!");
+        let offsets = [0, 1, 2, 3, 5, 7];
+        for (index, offset) in offsets.iter().enumerate() {
+            assert_eq!(a.index_to_utf16(index), *offset);
+            assert_eq!(a.utf16_to_index(*offset), index);
+        }
+        assert_eq!(a.utf16_to_index(4), 3);
+        assert_eq!(a.utf16_to_index(6), 4);
+        assert_eq!(a.index_to_utf16(a.len()), 8);
+    }
+
+    #[test]
+    fn test_encode_utf16() {
+        let text = "ab мир 🏡 e\u{301}\r\n".repeat(300);
+        let utf16 = text.encode_utf16().collect::<Vec<_>>();
+        let a = RipString::from_utf16(&utf16).unwrap();
+        assert_eq!(a, text);
+        assert_eq!(a.validate(), Ok(()));
+        assert!(a.encode_utf16().eq(utf16.iter().copied()));
+        assert_eq!(RipString::new().encode_utf16().next(), None);
+
+        let mut broken = utf16.clone();
+        broken[3000] = 0xDC00;
+        assert_eq!(
+            RipString::from_utf16(&broken).unwrap_err(),
+            RipError::UnpairedSurrogate(3000)
+        );
+    }
+}
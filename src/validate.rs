@@ -0,0 +1,89 @@
+use crate::error::InvariantError;
+use crate::splitter::MAX_BLOCK_SIZE;
+use crate::RipString;
+
+impl RipString {
+    /// Checks the structure of the string: segments are non-empty, at most a block long
+    /// and indexed contiguously from 0 in units and bytes, and the last edited segment
+    /// exists.
+    pub fn validate(&self) -> Result<(), InvariantError> {
+        if self.nodes.is_empty() {
+            return Err(InvariantError::NoSegments);
+        }
+        if self.last_edit >= self.nodes.len() {
+            return Err(InvariantError::LastEditOutOfBounds(self.last_edit));
+        }
+
+        let mut expected = 0;
+        let mut expected_byte = 0;
+        for (segment, node) in self.nodes.iter().enumerate() {
+            if node.index() != expected {
+                return Err(InvariantError::MisplacedSegment {
+                    segment,
+                    index: node.index(),
+                    expected,
+                });
+            }
+            if node.len() > MAX_BLOCK_SIZE {
+                return Err(InvariantError::OversizedSegment {
+                    segment,
+                    len: node.len(),
+                });
+            }
+            if node.len() == 0 && self.nodes.len() > 1 {
+                return Err(InvariantError::EmptySegment(segment));
+            }
+            if node.byte_index() != expected_byte || node.byte_len() != node.tp().byte_len() {
+                return Err(InvariantError::StaleByteOffset(segment));
+            }
+            expected += node.len();
+            expected_byte += node.byte_len();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::InvariantError;
+    use crate::segment::Segment;
+    use crate::RipString;
+    use alloc::vec;
+
+    #[test]
+    fn test_validate() {
+        let text = "line мир 🏡\n".repeat(300);
+        let mut a = RipString::from(text.as_str());
+        assert_eq!(a.validate(), Ok(()));
+        assert_eq!(RipString::new().validate(), Ok(()));
+
+        a.nodes[1].set_index(3);
+        assert!(matches!(
+            a.validate(),
+            Err(InvariantError::MisplacedSegment { segment: 1, .. })
+        ));
+        a.fix_index_from(0);
+        a.nodes.insert(2, Segment::default());
+        a.fix_index_from(0);
+        assert_eq!(a.validate(), Err(InvariantError::EmptySegment(2)));
+        a.nodes.remove(2);
+
+        a.nodes[2].set_byte_index(1);
+        assert_eq!(a.validate(), Err(InvariantError::StaleByteOffset(2)));
+        a.fix_index_from(0);
+        assert_eq!(a.validate(), Ok(()));
+
+        a.last_edit = a.nodes.len();
+        assert_eq!(
+            a.validate(),
+            Err(InvariantError::LastEditOutOfBounds(a.nodes.len()))
+        );
+        a.last_edit = 0;
+
+        a.nodes = vec![Segment::from("x".repeat(2000).into_bytes())];
+        assert!(matches!(
+            a.validate(),
+            Err(InvariantError::OversizedSegment { len: 2000, .. })
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+//! JavaScript bindings. All indices are UTF-16 code units, as in JavaScript strings.
+
+use crate::{RipError, RipString};
+use alloc::string::{String, ToString};
+use wasm_bindgen::prelude::{wasm_bindgen, JsError};
+
+#[wasm_bindgen(js_name = RipString)]
+pub struct JsRipString {
+    rip: RipString,
+}
+
+#[wasm_bindgen(js_class = RipString)]
+impl JsRipString {
+    #[wasm_bindgen(constructor)]
+    pub fn new(text: &str) -> JsRipString {
+        JsRipString {
+            rip: RipString::from(text),
+        }
+    }
+
+    /// Length in UTF-16 code units.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.rip.index_to_utf16(self.rip.len())
+    }
+
+    /// Replaces the text between UTF-16 offsets. Offsets inside a grapheme cluster are
+    /// moved to its start. Throws if `start` is after `end`.
+    pub fn edit(&mut self, start: usize, end: usize, text: &str) -> Result<(), JsError> {
+        self.try_edit(start, end, text)
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+
+    pub fn slice(&self, start: usize, end: usize) -> String {
+        let range = self.rip.utf16_to_index(start)..self.rip.utf16_to_index(end);
+        self.rip.collect_range(range)
+    }
+
+    #[wasm_bindgen(js_name = lineCount)]
+    pub fn line_count(&self) -> usize {
+        self.rip.line_count()
+    }
+
+    /// Returns the UTF-16 offset of the start of the line.
+    #[wasm_bindgen(js_name = lineStart)]
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.rip
+            .line_to_index(line)
+            .map(|index| self.rip.index_to_utf16(index))
+    }
+
+    /// Returns the line containing the UTF-16 offset.
+    #[wasm_bindgen(js_name = lineAt)]
+    pub fn line_at(&self, offset: usize) -> usize {
+        self.rip.index_to_line(self.rip.utf16_to_index(offset))
+    }
+
+    /// Returns the UTF-16 offset of the grapheme cluster boundary at or before `offset`.
+    #[wasm_bindgen(js_name = snapToGrapheme)]
+    pub fn snap_to_grapheme(&self, offset: usize) -> usize {
+        self.rip.index_to_utf16(self.rip.utf16_to_index(offset))
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.rip.to_string()
+    }
+}
+
+impl JsRipString {
+    fn try_edit(&mut self, start: usize, end: usize, text: &str) -> Result<(), RipError> {
+        let range = self.rip.utf16_to_index(start)..self.rip.utf16_to_index(end);
+        self.rip.try_edit(range, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wasm::JsRipString;
+    use crate::RipError;
+
+    #[test]
+    fn test_utf16_indices() {
+        let mut a = JsRipString::new("a🏡 мир\nok");
+        assert_eq!(a.length(), 10);
+        assert_eq!(a.slice(1, 3), "🏡");
+        assert_eq!(a.snap_to_grapheme(2), 1);
+        assert_eq!(a.line_start(1), Some(8));
+        assert_eq!(a.line_at(9), 1);
+
+        assert!(a.edit(2, 7, "!").is_ok());
+        assert_eq!(a.to_js_string(), "a!\nok");
+        assert_eq!(
+            a.try_edit(3, 1, "?"),
+            Err(RipError::InvertedRange { start: 3, end: 1 })
+        );
+        assert_eq!(a.to_js_string(), "a!\nok");
+        assert_eq!(a.line_count(), 2);
+        assert_eq!(a.line_start(2), None);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{RipSlice, RipString};
+use alloc::vec::Vec;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WhitespaceOptions {
+    /// Keep no-break, figure and narrow no-break spaces.
+    pub keep_non_breaking: bool,
+    /// Remove zero width spaces and byte order marks.
+    pub remove_zero_width: bool,
+}
+
+fn is_non_breaking(ch: char) -> bool {
+    matches!(ch, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+fn is_space(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{a0}' | '\u{1680}' | '\u{2000}'..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}'
+    )
+}
+
+fn is_zero_width(ch: char) -> bool {
+    matches!(ch, '\u{200b}' | '\u{2060}' | '\u{feff}')
+}
+
+impl RipString {
+    /// Replaces Unicode space variants with ASCII spaces and returns the number of
+    /// changed units.
+    pub fn normalize_whitespace(&mut self, options: WhitespaceOptions) -> usize {
+        let mut edits = Vec::new();
+        let mut index = 0;
+        self.for_each_unit(0..self.len(), |unit| {
+            let mut chars = unit.chars();
+            if let (Some(ch), None) = (chars.next(), chars.next()) {
+                if is_space(ch) && !(options.keep_non_breaking && is_non_breaking(ch)) {
+                    edits.push((index..index + 1, " "));
+                } else if options.remove_zero_width && is_zero_width(ch) {
+                    edits.push((index..index + 1, ""));
+                }
+            }
+            index += 1;
+        });
+
+        if !edits.is_empty() {
+            self.apply_edits(&edits);
+        }
+        edits.len()
+    }
+
+    /// Returns the text without leading and trailing whitespace.
+    pub fn trim(&self) -> RipSlice<'_> {
+        let start = self.skip_whitespace_forward(0);
+        let end = self.skip_whitespace_backward(self.len()).max(start);
+        self.slice(start..end)
+    }
+
+    /// Returns the text without leading whitespace.
+    pub fn trim_start(&self) -> RipSlice<'_> {
+        self.slice(self.skip_whitespace_forward(0)..)
+    }
+
+    /// Returns the text without trailing whitespace.
+    pub fn trim_end(&self) -> RipSlice<'_> {
+        self.slice(..self.skip_whitespace_backward(self.len()))
+    }
+
+    /// Removes leading and trailing whitespace and returns the number of removed units.
+    pub fn trim_in_place(&mut self) -> usize {
+        self.trim_end_in_place() + self.trim_start_in_place()
+    }
+
+    /// Removes leading whitespace and returns the number of removed units. Only the
+    /// segments holding it are touched.
+    pub fn trim_start_in_place(&mut self) -> usize {
+        let start = self.skip_whitespace_forward(0);
+        self.remove(..start);
+        start
+    }
+
+    /// Removes trailing whitespace and returns the number of removed units. Only the
+    /// segments holding it are touched.
+    pub fn trim_end_in_place(&mut self) -> usize {
+        let len = self.len();
+        let end = self.skip_whitespace_backward(len);
+        self.truncate(end);
+        len - end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RipString, WhitespaceOptions};
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let text = "a\u{a0}b\u{2009}c\u{3000}d\u{200b}e\u{202f}f\tg";
+        let mut a = RipString::from(text);
+        assert_eq!(a.normalize_whitespace(WhitespaceOptions::default()), 4);
+        assert_eq!(a.to_string(), "a b c d\u{200b}e f\tg");
+
+        let mut a = RipString::from(text);
+        let options = WhitespaceOptions {
+            keep_non_breaking: true,
+            remove_zero_width: true,
+        };
+        assert_eq!(a.normalize_whitespace(options), 3);
+        assert_eq!(a.to_string(), "a\u{a0}b c de\u{202f}f\tg");
+        assert_eq!(a.len(), 12);
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut a = RipString::from(" \t\u{3000}Hello, мир 🏡\r\n\n");
+        assert_eq!(a.trim().range(), 3..15);
+        assert_eq!(a.trim().to_string(), "Hello, мир 🏡");
+        assert_eq!(a.trim_start().to_string(), "Hello, мир 🏡\r\n\n");
+        assert_eq!(a.trim_end().to_string(), " \t\u{3000}Hello, мир 🏡");
+
+        assert_eq!(a.trim_end_in_place(), 3);
+        assert_eq!(a.to_string(), " \t\u{3000}Hello, мир 🏡");
+        assert_eq!(a.trim_in_place(), 3);
+        assert_eq!(a.to_string(), "Hello, мир 🏡");
+        assert_eq!(a.trim_in_place(), 0);
+
+        let mut a = RipString::from(" \n\t ");
+        assert_eq!(a.trim().range(), 4..4);
+        assert_eq!(a.trim_in_place(), 4);
+        assert!(a.is_empty());
+
+        let text = format!("{}x{}", " ".repeat(3000), "\n".repeat(3000));
+        let mut a = RipString::from(text.as_str());
+        assert_eq!(a.trim_in_place(), 6000);
+        assert_eq!(a.to_string(), "x");
+        assert_eq!(a.validate(), Ok(()));
+    }
+}
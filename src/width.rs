@@ -0,0 +1,42 @@
+use crate::RipString;
+use core::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+impl RipString {
+    /// Returns the display width of the given line in terminal columns.
+    pub fn width_of_line(&self, line: usize) -> Option<usize> {
+        self.line_range(line).map(|range| self.width_of(range))
+    }
+
+    /// Returns the display column of the given index within its line.
+    pub fn index_to_visual_col(&self, index: usize) -> usize {
+        let start = self
+            .line_to_index(self.index_to_line(index))
+            .unwrap_or_default();
+        self.width_of(start..index)
+    }
+
+    fn width_of(&self, range: Range<usize>) -> usize {
+        let mut width = 0;
+        self.for_each_unit(range, |unit| width += unit.width());
+        width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+
+    #[test]
+    fn test_width() {
+        let a = RipString::from("ab世界\ne\u{301}🏡x\n");
+        assert_eq!(a.width_of_line(0), Some(6));
+        assert_eq!(a.width_of_line(1), Some(4));
+        assert_eq!(a.width_of_line(2), Some(0));
+        assert_eq!(a.width_of_line(3), None);
+        assert_eq!(a.index_to_visual_col(3), 4);
+        assert_eq!(a.index_to_visual_col(5), 0);
+        assert_eq!(a.index_to_visual_col(7), 3);
+        assert_eq!(a.index_to_visual_col(8), 4);
+    }
+}
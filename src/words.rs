@@ -0,0 +1,241 @@
+use crate::RipString;
+#[cfg(feature = "word-dictionary")]
+use alloc::{collections::VecDeque, fmt::Debug, vec::Vec};
+use core::cmp::min;
+use core::ops::Range;
+
+/// Dictionary-based word breaking for languages written without spaces, such as Thai or
+/// Japanese.
+#[cfg(feature = "word-dictionary")]
+pub trait WordDictionary: Debug + Sync {
+    /// Returns the byte offsets of word boundaries inside `run`, a run of word
+    /// characters. Offsets that do not fall between units are ignored.
+    fn word_boundaries(&self, run: &str) -> Vec<usize>;
+}
+
+/// Class of a unit for word navigation. This is a heuristic on the first char of the
+/// unit, not UAX #29 word segmentation: apostrophes, periods and other punctuation always
+/// end a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WordClass {
+    Space,
+    Word,
+    Punct,
+}
+
+impl WordClass {
+    /// Classifies the unit by its first char: whitespace, alphanumeric or underscore, or
+    /// anything else.
+    fn heuristic(unit: &str) -> WordClass {
+        match unit.chars().next() {
+            Some(ch) if ch.is_whitespace() => WordClass::Space,
+            Some(ch) if ch.is_alphanumeric() || ch == '_' => WordClass::Word,
+            _ => WordClass::Punct,
+        }
+    }
+}
+
+/// Iterator over the ranges of words.
+pub struct Words<'a> {
+    rope: &'a RipString,
+    index: usize,
+    #[cfg(feature = "word-dictionary")]
+    pending: VecDeque<Range<usize>>,
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "word-dictionary")]
+        if let Some(word) = self.pending.pop_front() {
+            return Some(word);
+        }
+
+        self.index = self.rope.scan_while(self.index, |unit| {
+            WordClass::heuristic(unit) != WordClass::Word
+        });
+        if self.index == self.rope.len() {
+            return None;
+        }
+
+        let start = self.index;
+        self.index = self.rope.run_end(start, WordClass::Word);
+
+        #[cfg(feature = "word-dictionary")]
+        if let Some(dictionary) = self.rope.word_dictionary {
+            let (text, offsets) = self.rope.text_with_offsets(start..self.index);
+            let mut word_start = start;
+            for boundary in dictionary.word_boundaries(&text) {
+                if let Ok(unit) = offsets.binary_search(&boundary) {
+                    if start + unit > word_start && start + unit < self.index {
+                        self.pending.push_back(word_start..start + unit);
+                        word_start = start + unit;
+                    }
+                }
+            }
+            self.pending.push_back(word_start..self.index);
+            return self.pending.pop_front();
+        }
+
+        Some(start..self.index)
+    }
+}
+
+impl RipString {
+    /// Returns the ranges of words: runs of alphanumeric units and underscores. Unlike
+    /// UAX #29 word bounds, an apostrophe splits a word, so "o'neil" is two words.
+    pub fn words(&self) -> Words<'_> {
+        Words {
+            rope: self,
+            index: 0,
+            #[cfg(feature = "word-dictionary")]
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Returns the words intersecting the range.
+    pub(crate) fn words_in(&self, range: Range<usize>) -> impl Iterator<Item = Range<usize>> + '_ {
+        let index = if range.start < self.len() && self.word_class(range.start) == WordClass::Word {
+            self.run_start(range.start, WordClass::Word)
+        } else {
+            range.start
+        };
+        Words {
+            rope: self,
+            index,
+            #[cfg(feature = "word-dictionary")]
+            pending: VecDeque::new(),
+        }
+        .take_while(move |word| word.start < range.end)
+    }
+
+    /// Sets the dictionary used by `words` to break runs of word characters.
+    #[cfg(feature = "word-dictionary")]
+    pub fn set_word_dictionary(&mut self, dictionary: Option<&'static dyn WordDictionary>) {
+        self.word_dictionary = dictionary;
+    }
+
+    /// Returns the run of word, punctuation or whitespace units around `index`, as
+    /// selected by a double click. At the end of the text the preceding run is returned.
+    pub fn word_at(&self, index: usize) -> Range<usize> {
+        let len = self.len();
+        let index = if index >= len {
+            match len.checked_sub(1) {
+                Some(last) => last,
+                None => return 0..0,
+            }
+        } else {
+            index
+        };
+
+        let class = self.word_class(index);
+        self.run_start(index, class)..self.run_end(index, class)
+    }
+
+    /// Returns the start of the word or punctuation run following `index`, or the end of
+    /// the text.
+    pub fn next_word_start(&self, index: usize) -> usize {
+        let len = self.len();
+        if index >= len {
+            return len;
+        }
+
+        let mut index = index;
+        let class = self.word_class(index);
+        if class != WordClass::Space {
+            index = self.run_end(index, class);
+        }
+        if index < len && self.word_class(index) == WordClass::Space {
+            index = self.run_end(index, WordClass::Space);
+        }
+        index
+    }
+
+    /// Returns the start of the word or punctuation run preceding `index`, or 0.
+    pub fn prev_word_start(&self, index: usize) -> usize {
+        let mut index = min(index, self.len());
+        if index > 0 && self.word_class(index - 1) == WordClass::Space {
+            index = self.run_start(index - 1, WordClass::Space);
+        }
+        match index.checked_sub(1) {
+            Some(last) => self.run_start(last, self.word_class(last)),
+            None => 0,
+        }
+    }
+
+    fn word_class(&self, index: usize) -> WordClass {
+        let mut class = WordClass::Punct;
+        self.for_each_unit(index..index + 1, |unit| class = WordClass::heuristic(unit));
+        class
+    }
+
+    fn run_start(&self, index: usize, class: WordClass) -> usize {
+        self.scan_back_while(index, |unit| WordClass::heuristic(unit) == class)
+    }
+
+    fn run_end(&self, index: usize, class: WordClass) -> usize {
+        self.scan_while(index, |unit| WordClass::heuristic(unit) == class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_word_navigation() {
+        let a = RipString::from("fn  main() {\n    let мир_1 = 👍;\n}");
+        assert_eq!(a.next_word_start(0), 4);
+        assert_eq!(a.next_word_start(4), 8);
+        assert_eq!(a.next_word_start(8), 11);
+        assert_eq!(a.next_word_start(11), 17);
+        assert_eq!(a.next_word_start(100), 33);
+
+        assert_eq!(a.prev_word_start(4), 0);
+        assert_eq!(a.prev_word_start(17), 11);
+        assert_eq!(a.prev_word_start(23), 21);
+        assert_eq!(a.prev_word_start(2), 0);
+        assert_eq!(a.prev_word_start(100), 32);
+
+        assert_eq!(a.word_at(22), 21..26);
+        assert_eq!(a.word_at(2), 2..4);
+        assert_eq!(a.word_at(100), 32..33);
+        assert_eq!(RipString::new().word_at(0), 0..0);
+    }
+
+    #[test]
+    fn test_words() {
+        let a = RipString::from("Hello, мир! x_1 👍 ok");
+        assert_eq!(a.words().collect::<Vec<_>>(), [0..5, 7..10, 12..15, 18..20]);
+        let a = RipString::from("o'neil 3.14");
+        assert_eq!(a.words().collect::<Vec<_>>(), [0..1, 2..6, 7..8, 9..11]);
+        assert_eq!(RipString::from(" ,; ").words().count(), 0);
+    }
+
+    #[cfg(feature = "word-dictionary")]
+    #[test]
+    fn test_word_dictionary() {
+        use crate::WordDictionary;
+        use alloc::vec;
+
+        #[derive(Debug)]
+        struct Thai;
+
+        impl WordDictionary for Thai {
+            fn word_boundaries(&self, run: &str) -> Vec<usize> {
+                run.match_indices("ภาษา")
+                    .flat_map(|(pos, word)| vec![pos, pos + word.len()])
+                    .chain(vec![2, run.len()])
+                    .collect()
+            }
+        }
+
+        let mut a = RipString::from("สวัสดีภาษาไทย ok");
+        a.set_word_dictionary(Some(&Thai));
+        assert_eq!(a.words().collect::<Vec<_>>(), [0..4, 4..8, 8..11, 12..14]);
+        a.set_word_dictionary(None);
+        assert_eq!(a.words().count(), 2);
+    }
+}
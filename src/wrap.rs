@@ -0,0 +1,99 @@
+use crate::column::unit_width;
+use crate::RipString;
+use alloc::collections::VecDeque;
+use core::ops::Range;
+
+pub struct WrappedLines<'a> {
+    rope: &'a RipString,
+    width: usize,
+    next_line: Option<usize>,
+    pending: VecDeque<Range<usize>>,
+}
+
+impl<'a> Iterator for WrappedLines<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            let start = self.next_line?;
+            let (end, next_line) = self.rope.line_end(start);
+            self.next_line = next_line;
+            self.rope
+                .wrap_line(start..end, self.width, &mut self.pending);
+        }
+        self.pending.pop_front()
+    }
+}
+
+impl RipString {
+    /// Returns the ranges of visual lines when the text is wrapped at `width` columns.
+    ///
+    /// Lines are broken after whitespace when possible; words longer than `width` are
+    /// broken between grapheme clusters. Whitespace is allowed to hang past the limit.
+    pub fn wrapped_lines(&self, width: usize) -> WrappedLines<'_> {
+        WrappedLines {
+            rope: self,
+            width,
+            next_line: Some(0),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn wrap_line(&self, line: Range<usize>, width: usize, out: &mut VecDeque<Range<usize>>) {
+        let mut start = line.start;
+        let mut index = line.start;
+        let mut col = 0;
+        // Index after the last whitespace of the current visual line and its column.
+        let mut last_break = None;
+
+        self.for_each_unit(line.clone(), |unit| {
+            let unit_width = unit_width(unit);
+            let is_whitespace = unit.chars().all(char::is_whitespace);
+            while col + unit_width > width && !is_whitespace && index > start {
+                match last_break.take() {
+                    Some((at, at_col)) => {
+                        out.push_back(start..at);
+                        start = at;
+                        col -= at_col;
+                    }
+                    None => {
+                        out.push_back(start..index);
+                        start = index;
+                        col = 0;
+                    }
+                }
+            }
+
+            col += unit_width;
+            index += 1;
+            if is_whitespace {
+                last_break = Some((index, col));
+            }
+        });
+        out.push_back(start..line.end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RipString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_wrapped_lines() {
+        let a = RipString::from("Hello big world\n\nabcdefgh");
+        assert_eq!(
+            a.wrapped_lines(10).collect::<Vec<_>>(),
+            [0..10, 10..15, 16..16, 17..25]
+        );
+        assert_eq!(
+            a.wrapped_lines(5).collect::<Vec<_>>(),
+            [0..6, 6..10, 10..15, 16..16, 17..22, 22..25]
+        );
+
+        let empty = RipString::new();
+        let mut lines = empty.wrapped_lines(5);
+        assert_eq!(lines.next(), Some(0..0));
+        assert_eq!(lines.next(), None);
+    }
+}